@@ -1,50 +1,110 @@
-use std::io;
-
-use palette::Hsv;
-use rand::Rng;
+use std::{
+    env, fs, io,
+    path::{self, Path, PathBuf},
+};
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
 use ratatui::{
     DefaultTerminal, Frame,
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Stylize},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Widget},
 };
 
 use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
 
 use arboard::Clipboard;
 
+use palette::{Hsv, Mix};
+
 use crate::widgets::{
-    content::{hex2rgb, rgb2hsv},
+    contrast::ContrastMatrix,
+    export::ExportPopup,
+    file_picker::FilePicker,
+    help::HelpOverlay,
+    history::HistoryView,
+    library::LibraryBrowser,
+    sliders::{ChannelEditor, SliderMode},
     status_bar::StatusBar,
+    terminal_preview::TerminalPreview,
 };
 use crate::{
+    color_depth::ColorDepth,
+    color_palette::{ColorPalette, MAX_BLOCKS, MIN_BLOCKS},
+    color_parser, config,
+    cvd::CvdMode,
+    export::{self, ExportFormat},
+    generator::{self, ColorSpace},
+    history::{History, Snapshot},
+    keymap::{self, Action, Context, Keymap},
+    library::Library,
     margin,
+    notification::{Level, Notification},
+    palette_file::{self, PALETTE_FILE_EXTENSION, PaletteFile},
+    terminal_theme::TerminalTheme,
+    theory::{TheoryRegistry, custom},
     widgets::content::{ColorBlock, MainContent},
 };
 
-pub const HEX_CHARS: [char; 22] = [
-    'a', 'b', 'c', 'd', 'e', 'f', 'A', 'B', 'C', 'D', 'E', 'F', '0', '1', '2', '3', '4', '5', '6',
-    '7', '8', '9',
-];
+// degrees per scroll wheel tick over a block
+const HUE_SCROLL_STEP: f32 = 5.0;
+
+fn popup_area(area: Rect) -> Rect {
+    Rect {
+        x: area.width / 3,
+        y: area.height * 2 / 5,
+        width: area.width / 3,
+        height: area.height / 4,
+    }
+}
+
+// long enough for "oklch(62.5% 0.125 210.5deg / 50%)"
+pub const EDIT_COLOR_MAX_LEN: usize = 48;
 
 #[derive(Debug, PartialEq)]
 pub enum CurrentPage {
     Main,
     TheorySelector,
     EditColor,
+    SavePalette,
+    OpenPalette,
+    ImportPalette,
+    Library,
+    EnterSeed,
+    History,
+    Export,
+    TerminalTheme,
+    Sliders,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, EnumIter)]
-pub enum ColorTheories {
-    Analogous,
-    Complementary,
-    Triad,
-    Square,
+impl CurrentPage {
+    pub fn title(&self) -> &'static str {
+        match self {
+            CurrentPage::ImportPalette => "Import Palette",
+            page => page.context().title(),
+        }
+    }
+
+    pub fn context(&self) -> Context {
+        match self {
+            CurrentPage::Main => Context::Main,
+            CurrentPage::TheorySelector => Context::Theories,
+            CurrentPage::EditColor => Context::EditColor,
+            CurrentPage::SavePalette => Context::Save,
+            CurrentPage::OpenPalette | CurrentPage::ImportPalette => Context::Files,
+            CurrentPage::Library => Context::Library,
+            CurrentPage::EnterSeed => Context::Seed,
+            CurrentPage::History => Context::History,
+            CurrentPage::Export => Context::Export,
+            CurrentPage::TerminalTheme => Context::TerminalTheme,
+            CurrentPage::Sliders => Context::Sliders,
+        }
+    }
 }
 
 pub struct App {
@@ -54,18 +114,58 @@ pub struct App {
 
     pub theory_selector_state: ListState,
     pub current_page: CurrentPage,
-    pub current_color_theory: ColorTheories,
+    pub theories: TheoryRegistry,
+    pub current_color_theory: String,
+    pub color_space: ColorSpace,
+
+    pub keymap: Keymap,
+    pub show_help: bool,
+
+    pub show_contrast: bool,
+    pub cvd_mode: CvdMode,
+    pub color_depth: ColorDepth,
 
     pub title: &'static str,
-    pub color_block_count: usize,
 
-    pub color_blocks: [Option<ColorBlock>; 9],
-    pub selected_block_id: usize,
+    pub palette: ColorPalette,
+    // first block on screen when they don't all fit
+    pub block_scroll: usize,
+
+    pub seed: Option<u64>,
+    pub seed_field: String,
 
-    pub status_bar_msg: &'static str,
+    pub history: History,
+    pub history_state: ListState,
+
+    pub export_format: ExportFormat,
+    pub export_block_id: usize,
+    pub export_message: Option<Result<String, String>>,
+    // existing file the user was warned about, writing it again replaces it
+    pub export_overwrite: Option<PathBuf>,
+
+    pub notification: Option<Notification>,
 
     pub edit_color_field: String,
 
+    pub slider_mode: SliderMode,
+    pub slider_channel: usize,
+    // history gets one entry per visit to the slider page, not one per nudge
+    pub slider_recorded: bool,
+
+    pub frame_area: Rect,
+    // block the scroll wheel is rotating, so a run of ticks is one undo step
+    pub scrolled_block: Option<usize>,
+
+    pub save_file_field: String,
+    pub save_tags_field: String,
+    pub save_tags_focused: bool,
+    pub save_file_error: Option<String>,
+    pub file_picker: Option<FilePicker>,
+
+    pub library: Library,
+    pub library_query: String,
+    pub library_state: ListState,
+
     pub exit: bool,
 }
 
@@ -79,20 +179,33 @@ impl App {
     }
 
     fn draw(&mut self, frame: &mut Frame) {
+        // kept for matching mouse clicks to what was drawn
+        self.frame_area = frame.area();
+
+        let (main_area, _, _) = self.split_screen(frame.area());
+        self.block_scroll = MainContent::scroll_to(
+            self.block_scroll,
+            self.palette.selected_index(),
+            self.palette.len(),
+            main_area.width,
+        );
+
         frame.render_widget(&*self, frame.area());
 
-        let popup_area = Rect {
-            x: frame.area().width / 3,
-            y: frame.area().height * 2 / 5,
-            width: frame.area().width / 3,
-            height: frame.area().height / 4,
-        };
+        let popup_area = popup_area(frame.area());
 
         if self.current_page == CurrentPage::TheorySelector {
             // SETTINGS POPUP
 
-            let popup_list_items: Vec<ListItem> = ColorTheories::iter()
-                .map(|t| ListItem::new(format!("{:?}", t)))
+            let popup_list_items: Vec<ListItem> = self
+                .theories
+                .iter()
+                .map(|t| {
+                    ListItem::new(Line::from(vec![
+                        Span::from(t.name().to_string()),
+                        Span::from(format!("  {}", t.description())).dim(),
+                    ]))
+                })
                 .collect();
 
             let popup_list = List::new(popup_list_items)
@@ -104,109 +217,636 @@ impl App {
                 )
                 .highlight_symbol(">");
 
-            frame.render_widget(Clear, popup_area);
-            frame.render_stateful_widget(popup_list, popup_area, &mut self.theory_selector_state);
+            let theory_area = self.theory_area(frame.area());
+
+            frame.render_widget(Clear, theory_area);
+            frame.render_stateful_widget(popup_list, theory_area, &mut self.theory_selector_state);
         } else if self.current_page == CurrentPage::EditColor {
             let layout = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints(vec![Constraint::Fill(1), Constraint::Fill(1)])
-                .split(popup_area);
+                .constraints(vec![Constraint::Length(2), Constraint::Fill(1)])
+                .split(popup_area.inner(margin!(1, 1)));
 
             let block = Block::default()
                 .title(" Edit Color ")
+                .title_bottom(" hex, rgb(), hsl(), hsv(), oklch(), lab() or a name ")
                 .borders(Borders::ALL)
                 .border_type(BorderType::Plain);
 
+            frame.render_widget(Clear, popup_area);
             frame.render_widget(block, popup_area);
 
-            // DOESNT WORK FOR SOME REASON,
-            // let par_w_blink = Paragraph::new(Line::from(vec![
-            //     Span::from(format!(" > {}", "Hello World")),
-            //     Span::from("█").add_modifier(Modifier::SLOW_BLINK),
-            // ]))
-            // .block(block);
+            let input = Line::from(format!(" > {}_", &self.edit_color_field));
 
-            let (r, g, b) = hex2rgb(&self.edit_color_field);
+            let (status, preview) = match color_parser::parse(&self.edit_color_field) {
+                Ok(parsed) => {
+                    let (r, g, b) = parsed.rgb8();
+                    let mut notes = String::new();
 
-            let par = Paragraph::new(format!(" Enter HEX: {}", &self.edit_color_field));
-            let overview = Paragraph::new(Line::from("Overview:").add_modifier(Modifier::REVERSED))
-                .block(Block::new().bg(Color::Rgb(r, g, b)));
+                    if parsed.alpha < 1.0 {
+                        notes.push_str("  alpha ignored");
+                    }
+                    if parsed.clipped {
+                        notes.push_str("  clipped to sRGB");
+                    }
+
+                    (
+                        Line::from(vec![
+                            Span::from(format!(" #{r:02X}{g:02X}{b:02X}  rgb({r}, {g}, {b})")),
+                            Span::from(notes).fg(Color::Yellow),
+                        ]),
+                        Some(Color::Rgb(r, g, b)),
+                    )
+                }
+                Err(err) => (Line::from(format!(" {err}")).fg(Color::LightRed), None),
+            };
 
-            frame.render_widget(Clear, popup_area.inner(margin!(1, 1)));
-            frame.render_widget(par, layout[0].inner(margin!(1, 1)));
-            frame.render_widget(overview, layout[1].inner(margin!(1, 1)));
+            frame.render_widget(Paragraph::new(vec![input, status]), layout[0]);
+
+            // the block's current color until the input parses
+            let preview = preview.or_else(|| {
+                self.palette.selected().map(|block| {
+                    let (r, g, b) = block.get_rgb_values();
+                    Color::Rgb(r, g, b)
+                })
+            });
+
+            if let Some(color) = preview {
+                let overview =
+                    Paragraph::new(Line::from("Preview").add_modifier(Modifier::REVERSED))
+                        .block(Block::new().bg(color));
+
+                frame.render_widget(overview, layout[1].inner(margin!(1, 1)));
+            }
+        } else if self.current_page == CurrentPage::Sliders {
+            let slider_area = Rect {
+                x: frame.area().width / 6,
+                y: frame.area().height / 3,
+                width: frame.area().width * 2 / 3,
+                height: 10,
+            }
+            .intersection(frame.area());
+
+            if let Some(block) = self.palette.selected() {
+                frame.render_widget(
+                    ChannelEditor {
+                        block,
+                        mode: self.slider_mode,
+                        selected: self.slider_channel,
+                    },
+                    slider_area,
+                );
+            }
+        } else if self.current_page == CurrentPage::TerminalTheme {
+            let preview_area = Rect {
+                x: frame.area().width / 6,
+                y: frame.area().height / 8,
+                width: frame.area().width * 2 / 3,
+                height: frame.area().height * 3 / 4,
+            };
+
+            let theme = TerminalTheme::from_blocks(&self.palette);
+            frame.render_widget(TerminalPreview::new(&theme), preview_area);
+        } else if self.current_page == CurrentPage::Export {
+            let export_area = Rect {
+                x: frame.area().width / 8,
+                y: frame.area().height / 8,
+                width: frame.area().width * 3 / 4,
+                height: frame.area().height * 3 / 4,
+            };
+
+            let output_path = self.export_format.output_path(&self.save_file_field);
+
+            frame.render_widget(
+                ExportPopup {
+                    format: self.export_format,
+                    name: &self.save_file_field,
+                    color_blocks: &self.palette,
+                    selected: self.export_block_id,
+                    output_path: &output_path,
+                    message: self.export_message.as_ref(),
+                },
+                export_area,
+            );
+        } else if self.current_page == CurrentPage::History {
+            let history_area = Rect {
+                x: frame.area().width / 4,
+                y: frame.area().height / 8,
+                width: frame.area().width / 2,
+                height: frame.area().height * 3 / 4,
+            };
+
+            frame.render_stateful_widget(
+                HistoryView::new(&self.history),
+                history_area,
+                &mut self.history_state,
+            );
+        } else if self.current_page == CurrentPage::EnterSeed {
+            let block = Block::default()
+                .title(" Replay Seed ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
+
+            let lines = vec![
+                Line::from(format!(" Seed: {}_", &self.seed_field)),
+                Line::from(format!(" Theory: {}", self.current_color_theory)).dim(),
+            ];
+
+            frame.render_widget(Clear, popup_area);
+            frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+        } else if self.current_page == CurrentPage::SavePalette {
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Length(1), Constraint::Fill(1)])
+                .split(popup_area.inner(margin!(1, 1)));
+
+            let block = Block::default()
+                .title(" Save Palette ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
+
+            let (name_cursor, tags_cursor) = if self.save_tags_focused {
+                ("", "_")
+            } else {
+                ("_", "")
+            };
+
+            let mut lines = vec![
+                Line::from(format!(" Name: {}{name_cursor}", &self.save_file_field)),
+                Line::from(format!(" Tags: {}{tags_cursor}", &self.save_tags_field)),
+                Line::from(
+                    format!(
+                        " -> {}",
+                        palette_file::palette_path(&self.save_file_field).display()
+                    )
+                    .dim(),
+                ),
+            ];
+
+            if let Some(error) = &self.save_file_error {
+                lines.push(Line::from(format!(" {error}")).fg(Color::LightRed));
+            }
+
+            frame.render_widget(Clear, popup_area);
+            frame.render_widget(block, popup_area);
+            frame.render_widget(Paragraph::new(lines), layout[0].union(layout[1]));
+        } else if (self.current_page == CurrentPage::OpenPalette
+            || self.current_page == CurrentPage::ImportPalette)
+            && let Some(file_picker) = self.file_picker.as_mut()
+        {
+            let picker_area = Rect {
+                y: frame.area().height / 4,
+                height: frame.area().height / 2,
+                ..popup_area
+            };
+
+            frame.render_widget(file_picker, picker_area);
+        } else if self.current_page == CurrentPage::Library {
+            let library_area = Rect {
+                x: frame.area().width / 6,
+                y: frame.area().height / 8,
+                width: frame.area().width * 2 / 3,
+                height: frame.area().height * 3 / 4,
+            };
+
+            let browser = LibraryBrowser::new(
+                self.library.filtered(&self.library_query),
+                &self.library_query,
+                self.library.broken.len(),
+            );
+
+            frame.render_stateful_widget(browser, library_area, &mut self.library_state);
+        }
+
+        if self.show_help {
+            let help_area = Rect {
+                x: frame.area().width / 6,
+                y: frame.area().height / 8,
+                width: frame.area().width * 2 / 3,
+                height: frame.area().height * 3 / 4,
+            };
+
+            frame.render_widget(
+                HelpOverlay::new(&self.keymap, self.current_page.context()),
+                help_area,
+            );
         }
+
+        self.color_depth.quantize_buffer(frame.buffer_mut());
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
+        // wake up when the notification runs out so it gets cleared
+        if let Some(notification) = &self.notification
+            && !event::poll(notification.remaining())?
+        {
+            self.notification = None;
+            return Ok(());
+        }
+
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                self.scrolled_block = None;
                 self.handle_key_event(key_event)
             }
+            Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
             _ => {}
         };
         Ok(())
     }
 
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        // like a key press, a click or scroll only closes the help
+        if self.show_help {
+            if matches!(
+                mouse_event.kind,
+                MouseEventKind::Down(_) | MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+            ) {
+                self.show_help = false;
+            }
+            return;
+        }
+
+        let position = Position::new(mouse_event.column, mouse_event.row);
+
+        match self.current_page {
+            CurrentPage::Main => {
+                let (main_area, _, _) = self.split_screen(self.frame_area);
+
+                let areas =
+                    MainContent::block_areas(main_area, self.palette.len(), self.block_scroll);
+
+                let Some(&(idx, area)) = areas.iter().find(|(_, area)| area.contains(position))
+                else {
+                    return;
+                };
+
+                match mouse_event.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        self.scrolled_block = None;
+
+                        // the top row is the LOCKED/UNLOCKED strip
+                        if mouse_event.row == area.y {
+                            self.toggle_lock(idx);
+                        } else {
+                            self.palette.select(idx);
+                        }
+                    }
+                    MouseEventKind::ScrollUp => self.rotate_hue(idx, HUE_SCROLL_STEP),
+                    MouseEventKind::ScrollDown => self.rotate_hue(idx, -HUE_SCROLL_STEP),
+                    _ => {}
+                }
+            }
+
+            CurrentPage::TheorySelector => {
+                if mouse_event.kind != MouseEventKind::Down(MouseButton::Left) {
+                    return;
+                }
+
+                let theory_area = self.theory_area(self.frame_area);
+                let items = theory_area.inner(margin!(1, 1));
+
+                if items.contains(position) {
+                    let idx = (position.y - items.y) as usize + self.theory_selector_state.offset();
+                    self.select_theory(idx);
+                } else if !theory_area.contains(position) {
+                    self.current_page = CurrentPage::Main;
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    // tall enough for every theory plus the borders
+    fn theory_area(&self, area: Rect) -> Rect {
+        let popup_area = popup_area(area);
+
+        Rect {
+            height: popup_area
+                .height
+                .max(self.theories.len() as u16 + 2)
+                .min(area.height.saturating_sub(popup_area.y)),
+            ..popup_area
+        }
+    }
+
+    fn select_theory(&mut self, idx: usize) {
+        if let Some(theory) = self.theories.iter().nth(idx) {
+            self.current_color_theory = theory.name().to_string();
+            self.theory_selector_state.select(Some(idx));
+            self.current_page = CurrentPage::Main;
+        }
+    }
+
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+
+        let action = self.keymap.action(self.current_page.context(), &key_event);
+
+        if action == Some(Action::Help) {
+            self.show_help = true;
+            return;
+        }
+        // plain characters go to text fields when they aren't bound
+        let typing = !key_event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+
         match self.current_page {
-            CurrentPage::Main => match (key_event.code, key_event.modifiers) {
-                (KeyCode::Char('q'), _) => self.exit(),
-                (KeyCode::Left, _) => self.decrement_counter(),
-                (KeyCode::Right, _) => self.increment_counter(),
+            CurrentPage::Main => match action {
+                Some(Action::Quit) => self.exit(),
+                Some(Action::SelectPrevious) => self.palette.select_previous(),
+                Some(Action::SelectNext) => self.palette.select_next(),
 
-                (KeyCode::Char('a'), _) if self.color_block_count < 9 => self.add_block(),
-                (KeyCode::Char('d'), _) if self.color_block_count > 3 => self.del_block(),
+                Some(Action::AddBlock | Action::AddBlockBefore | Action::DuplicateBlock)
+                    if self.palette.is_full() =>
+                {
+                    self.notify(Level::Warn, format!("{MAX_BLOCKS} blocks is the most"))
+                }
+                Some(Action::AddBlock) => self.add_block(1),
+                Some(Action::AddBlockBefore) => self.add_block(0),
+                Some(Action::DuplicateBlock) => {
+                    self.history.record(self.snapshot("Duplicate Block"));
+                    self.palette.duplicate_selected();
+                }
+
+                Some(Action::MoveBlockLeft) => self.move_block(-1),
+                Some(Action::MoveBlockRight) => self.move_block(1),
+                Some(Action::SwapBlock) => self.swap_block(),
 
-                (KeyCode::Char('x'), _) => {
+                Some(Action::DeleteBlock) if self.palette.can_remove() => self.del_block(),
+                Some(Action::DeleteBlock) => {
+                    self.notify(Level::Warn, format!("{MIN_BLOCKS} blocks is the least"))
+                }
+
+                Some(Action::Theories) => {
                     self.theory_selector_state.select_first();
                     self.current_page = CurrentPage::TheorySelector
                 }
 
-                (KeyCode::Char('z'), _) => {
+                Some(Action::EditColor) => {
                     self.current_page = CurrentPage::EditColor;
                 }
 
-                (KeyCode::Char('l'), _) => {
-                    if let Some(block) = self.color_blocks[self.selected_block_id].as_mut() {
-                        block.locked = !block.locked;
+                Some(Action::ToggleLock) => self.toggle_lock(self.palette.selected_index()),
+                Some(Action::LockBlock(num)) => self.toggle_lock(num as usize - 1),
+
+                Some(Action::CopyHex) => {
+                    if let Some(hex) = self.palette.selected().map(|block| block.get_hex()) {
+                        match self.clipboard.set_text(hex.clone()) {
+                            Ok(()) => self.notify(Level::Info, format!("Copied {hex}")),
+                            Err(err) => self.notify(Level::Error, format!("Clipboard: {err}")),
+                        }
                     }
                 }
 
-                (KeyCode::Char('c'), _) => {
-                    self.clipboard
-                        .set_text(self.color_blocks[self.selected_block_id].unwrap().get_hex())
-                        .unwrap();
+                Some(Action::Save) => {
+                    self.save_file_error = None;
+                    self.current_page = CurrentPage::SavePalette;
                 }
 
-                (KeyCode::Char(c), KeyModifiers::ALT) if ('1'..='9').contains(&c) => {
-                    let num = c.to_digit(10).unwrap() as usize;
-                    self.toggle_lock(num);
+                Some(Action::Open) => self.open_file_picker(),
+                Some(Action::Import) => self.open_import_picker(),
+
+                Some(Action::Library) => self.open_library(),
+
+                Some(Action::Export) => self.open_export(None),
+
+                Some(Action::TerminalTheme) => self.current_page = CurrentPage::TerminalTheme,
+
+                Some(Action::Sliders) => {
+                    self.slider_recorded = false;
+                    self.current_page = CurrentPage::Sliders;
                 }
 
-                (KeyCode::Char(' '), _) => match self.current_color_theory {
-                    ColorTheories::Analogous => self.generate_analogous(),
-                    ColorTheories::Complementary => self.generate_complementary(),
-                    ColorTheories::Triad => self.generate_triad(),
-                    ColorTheories::Square => self.generate_square(),
+                Some(Action::ToggleColorSpace) => self.color_space = self.color_space.toggle(),
+
+                Some(Action::ToggleContrast) => self.show_contrast = !self.show_contrast,
+
+                Some(Action::CycleCvd) => self.cvd_mode = self.cvd_mode.next(),
+
+                Some(Action::Generate) => self.generate(generator::random_seed()),
+
+                Some(Action::Undo) => self.undo(),
+                Some(Action::Redo) => self.redo(),
+
+                Some(Action::History) => {
+                    self.history_state.select_first();
+                    self.current_page = CurrentPage::History;
+                }
+
+                Some(Action::EnterSeed) => {
+                    self.seed_field.clear();
+                    self.current_page = CurrentPage::EnterSeed;
+                }
+
+                _ => {}
+            },
+            CurrentPage::TheorySelector => match action {
+                Some(Action::Close) => self.current_page = CurrentPage::Main,
+
+                Some(Action::First) => self.theory_selector_state.select_first(),
+                Some(Action::Last) => self.theory_selector_state.select_last(),
+                Some(Action::Up) => self.theory_selector_state.select_previous(),
+                Some(Action::Down) => self.theory_selector_state.select_next(),
+
+                Some(Action::Confirm) => match self.theory_selector_state.selected() {
+                    Some(selected) => self.select_theory(selected),
+                    None => self.current_page = CurrentPage::Main,
                 },
 
                 _ => {}
             },
-            CurrentPage::TheorySelector => match (key_event.code, key_event.modifiers) {
-                (KeyCode::Char('x'), _) | (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => {
-                    self.current_page = CurrentPage::Main
+
+            CurrentPage::SavePalette => match (action, key_event.code) {
+                (Some(Action::Close), _) => self.current_page = CurrentPage::Main,
+
+                (Some(Action::SwitchField), _) => {
+                    self.save_tags_focused = !self.save_tags_focused;
+                }
+
+                (Some(Action::Confirm), _) if !self.save_file_field.trim().is_empty() => {
+                    self.save_palette();
+                }
+
+                (None, KeyCode::Char(c)) if typing && self.save_tags_focused => {
+                    self.save_tags_field.push(c);
+                }
+
+                (None, KeyCode::Char(c)) if typing && !path::is_separator(c) => {
+                    self.save_file_field.push(c);
+                }
+
+                (None, KeyCode::Backspace) => {
+                    if self.save_tags_focused {
+                        self.save_tags_field.pop();
+                    } else {
+                        self.save_file_field.pop();
+                    }
+                }
+
+                _ => {}
+            },
+
+            CurrentPage::OpenPalette | CurrentPage::ImportPalette => match action {
+                Some(Action::Close) => self.current_page = CurrentPage::Main,
+
+                Some(Action::Up) => {
+                    if let Some(file_picker) = self.file_picker.as_mut() {
+                        file_picker.select_previous();
+                    }
                 }
 
-                (KeyCode::Left, _) => self.theory_selector_state.select_first(),
-                (KeyCode::Right, _) => self.theory_selector_state.select_last(),
-                (KeyCode::Up, _) => self.theory_selector_state.select_previous(),
-                (KeyCode::Down, _) => self.theory_selector_state.select_next(),
+                Some(Action::Down) => {
+                    if let Some(file_picker) = self.file_picker.as_mut() {
+                        file_picker.select_next();
+                    }
+                }
+
+                Some(Action::Parent) => {
+                    if let Some(file_picker) = self.file_picker.as_mut() {
+                        file_picker.go_up();
+                    }
+                }
 
-                (KeyCode::Enter, _) | (KeyCode::Char(' '), _) => {
-                    if let Some(selected) = self.theory_selector_state.selected() {
-                        let theories: Vec<ColorTheories> = ColorTheories::iter().collect();
-                        self.current_color_theory = theories[selected];
+                Some(Action::Confirm) => {
+                    if let Some(path) = self
+                        .file_picker
+                        .as_mut()
+                        .and_then(|file_picker| file_picker.open_selected())
+                    {
+                        if self.current_page == CurrentPage::ImportPalette {
+                            self.import_palette(&path);
+                        } else {
+                            self.open_palette(&path);
+                        }
+                    }
+                }
+
+                _ => {}
+            },
+
+            CurrentPage::Library => match (action, key_event.code) {
+                (Some(Action::Close), _) => self.current_page = CurrentPage::Main,
+
+                (Some(Action::Up), _) => self.library_state.select_previous(),
+                (Some(Action::Down), _) => self.library_state.select_next(),
+
+                (Some(Action::Confirm), _) => {
+                    let selected = self.library_state.selected().and_then(|idx| {
+                        self.library
+                            .filtered(&self.library_query)
+                            .get(idx)
+                            .map(|entry| (entry.file.clone(), entry.name.clone()))
+                    });
+
+                    if let Some((file, name)) = selected {
+                        self.apply_palette_file(file, name);
+                    }
+                }
+
+                (None, KeyCode::Char(c)) if typing => {
+                    self.library_query.push(c);
+                    self.library_state.select_first();
+                }
+
+                (None, KeyCode::Backspace) => {
+                    self.library_query.pop();
+                    self.library_state.select_first();
+                }
+
+                _ => {}
+            },
+
+            CurrentPage::Sliders => match action {
+                Some(Action::Close) => self.current_page = CurrentPage::Main,
+
+                Some(Action::NextMode) => self.slider_mode = self.slider_mode.next(),
+
+                Some(Action::Up) => self.slider_channel = (self.slider_channel + 2) % 3,
+                Some(Action::Down) => self.slider_channel = (self.slider_channel + 1) % 3,
+
+                Some(Action::Decrease) => self.nudge_channel(-1.0, false),
+                Some(Action::Increase) => self.nudge_channel(1.0, false),
+                Some(Action::DecreaseCoarse) => self.nudge_channel(-1.0, true),
+                Some(Action::IncreaseCoarse) => self.nudge_channel(1.0, true),
+
+                _ => {}
+            },
+
+            CurrentPage::TerminalTheme => match action {
+                Some(Action::Close) => self.current_page = CurrentPage::Main,
+
+                Some(Action::Export) => self.open_export(Some(ExportFormat::Alacritty)),
+
+                _ => {}
+            },
+
+            CurrentPage::Export => match (action, key_event.code) {
+                (Some(Action::Close), _) => self.current_page = CurrentPage::Main,
+
+                (Some(Action::PreviousFormat), _) => self.cycle_export_format(-1),
+                (Some(Action::NextFormat), _) => self.cycle_export_format(1),
+
+                (Some(Action::Up), _) => {
+                    self.export_block_id = self.export_block_id.saturating_sub(1)
+                }
+                (Some(Action::Down), _) => {
+                    self.export_block_id =
+                        (self.export_block_id + 1).min(self.palette.len().saturating_sub(1))
+                }
+
+                (Some(Action::Copy), _) if self.export_format.is_binary() => {
+                    self.export_result(Err(format!(
+                        "{} is binary, write it to a file instead",
+                        self.export_format
+                    )));
+                }
+
+                (Some(Action::Copy), _) => {
+                    let text =
+                        export::preview(self.export_format, &self.save_file_field, &self.palette);
+
+                    let result = self
+                        .clipboard
+                        .set_text(text)
+                        .map(|_| format!("Copied {} to clipboard", self.export_format))
+                        .map_err(|err| err.to_string());
+                    self.export_result(result);
+                }
+
+                (Some(Action::Confirm), _) => self.write_export(),
+
+                (None, KeyCode::Char(c)) if typing => {
+                    if let Some(block) = self.export_block() {
+                        block.name.push(c);
+                    }
+                }
+
+                (None, KeyCode::Backspace) => {
+                    if let Some(block) = self.export_block() {
+                        block.name.pop();
+                    }
+                }
+
+                _ => {}
+            },
+
+            CurrentPage::History => match action {
+                Some(Action::Close) => self.current_page = CurrentPage::Main,
+
+                Some(Action::Up) => self.history_state.select_previous(),
+                Some(Action::Down) => self.history_state.select_next(),
+
+                Some(Action::Confirm) => {
+                    if let Some(selected) = self.history_state.selected()
+                        && let Some(snapshot) = self.history.jump(selected, self.snapshot(""))
+                    {
+                        self.restore(snapshot);
                         self.current_page = CurrentPage::Main;
                     }
                 }
@@ -214,277 +854,462 @@ impl App {
                 _ => {}
             },
 
-            CurrentPage::EditColor => match (key_event.code, key_event.modifiers) {
-                (KeyCode::Char('z'), _) | (KeyCode::Char('q'), _) => {
-                    self.current_page = CurrentPage::Main
+            CurrentPage::EnterSeed => match (action, key_event.code) {
+                (Some(Action::Close), _) => self.current_page = CurrentPage::Main,
+
+                (Some(Action::Confirm), _) => {
+                    // anything that doesn't fit in a u64 just stays in the field
+                    if let Ok(seed) = self.seed_field.parse::<u64>() {
+                        self.generate(seed);
+                        self.current_page = CurrentPage::Main;
+                    }
                 }
 
-                (KeyCode::Char(c), _)
-                    if HEX_CHARS.contains(&c) && self.edit_color_field.len() < 6 =>
+                (None, KeyCode::Char(c))
+                    if typing && c.is_ascii_digit() && self.seed_field.len() < 20 =>
                 {
-                    self.edit_color_field.push(c);
+                    self.seed_field.push(c);
                 }
 
-                // doesnt work gonna look later
-                (KeyCode::Backspace, KeyModifiers::CONTROL) => {
-                    self.edit_color_field = String::new();
+                (None, KeyCode::Backspace) => {
+                    self.seed_field.pop();
                 }
 
-                (KeyCode::Backspace, _) => {
-                    self.edit_color_field.pop();
-                }
+                _ => {}
+            },
 
-                (KeyCode::Enter, _) => {
-                    if let Some(block) = self.color_blocks[self.selected_block_id].as_mut() {
-                        let (r, g, b) = hex2rgb(&self.edit_color_field);
-                        let (h, s, v) = rgb2hsv(r, g, b);
-                        block.hsv = Hsv::new(h, s, v);
+            CurrentPage::EditColor => match (action, key_event.code) {
+                (Some(Action::Close), _) => self.current_page = CurrentPage::Main,
+
+                (Some(Action::Clear), _) => self.edit_color_field = String::new(),
+
+                (Some(Action::Confirm), _) => {
+                    // invalid input stays in the field with its error showing
+                    if let Ok(parsed) = color_parser::parse(&self.edit_color_field)
+                        && self.palette.selected().is_some()
+                    {
+                        self.history.record(self.snapshot("Edit Color"));
+
+                        if let Some(block) = self.palette.selected_mut() {
+                            block.hsv = parsed.hsv();
+                        }
                         self.edit_color_field = String::new();
                     }
                 }
 
+                (None, KeyCode::Char(c))
+                    if typing && self.edit_color_field.chars().count() < EDIT_COLOR_MAX_LEN =>
+                {
+                    self.edit_color_field.push(c);
+                }
+
+                (None, KeyCode::Backspace) => {
+                    self.edit_color_field.pop();
+                }
+
                 _ => {}
             },
         }
     }
 
-    fn get_locked_blocks(&mut self) -> Vec<Option<ColorBlock>> {
-        self.color_blocks
-            .iter()
-            .filter(|block| block.is_some())
-            .filter(|block| block.unwrap().locked)
-            .cloned()
-            .collect()
-    }
-
-    fn generate_square(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let rand_rate = 8; // Lower randomness for cleaner square relationships
-
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-        } else {
-            // Generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-            }
+    fn notify(&mut self, level: Level, message: impl Into<String>) {
+        self.notification = Some(Notification::new(level, message));
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    /// Palette, contrast panel (when shown) and status bar areas.
+    fn split_screen(&self, area: Rect) -> (Rect, Option<Rect>, Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Fill(1), Constraint::Length(3)])
+            .split(area);
+
+        if !self.show_contrast {
+            return (layout[0], None, layout[1]);
         }
 
-        for (i, block) in self.color_blocks.iter_mut().enumerate() {
-            if let Some(color_block) = block {
-                if !color_block.locked {
-                    let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
+        // header, a spacer and two lines per block, leaving room for the blocks
+        let matrix_height = (3 + 2 * self.palette.len() as u16).min(layout[0].height / 2);
 
-                    // Create square colors: base, base+90°, base+180°, base+270°
-                    let new_hue = match i % 4 {
-                        0 => (base_hue + randomness) % 360.0,         // Primary
-                        1 => (base_hue + 90.0 + randomness) % 360.0,  // First square
-                        2 => (base_hue + 180.0 + randomness) % 360.0, // Complement
-                        3 => (base_hue + 270.0 + randomness) % 360.0, // Second square
-                        _ => unreachable!(),
-                    };
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Fill(1), Constraint::Length(matrix_height)])
+            .split(layout[0]);
 
-                    let new_sat = if locked_blocks.is_empty() {
-                        rng.random_range(55..80) as f32 / 100.0 // Balanced saturation for square harmony
-                    } else {
-                        color_block.hsv.saturation
-                    };
+        (split[0], Some(split[1]), layout[1])
+    }
 
-                    let new_val = if locked_blocks.is_empty() {
-                        rng.random_range(50..75) as f32 / 100.0
-                    } else {
-                        color_block.hsv.value
-                    };
+    fn rotate_hue(&mut self, idx: usize, degrees: f32) {
+        if idx >= self.palette.len() {
+            return;
+        }
 
-                    color_block.change_color(new_hue, new_sat, new_val);
-                }
-            }
+        // a run of scroll ticks on one block is a single undo step
+        if self.scrolled_block != Some(idx) {
+            self.history.record(self.snapshot("Rotate Hue"));
+            self.scrolled_block = Some(idx);
+        }
+
+        if let Some(block) = self.palette.get_mut(idx) {
+            let (hue, sat, val) = block.get_hsv_values();
+            block.change_color((hue + degrees).rem_euclid(360.0), sat, val);
         }
     }
 
-    fn generate_triad(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let rand_rate = 8; // Lower randomness for cleaner triadic relationships
+    fn toggle_lock(&mut self, idx: usize) {
+        if idx >= self.palette.len() {
+            return;
+        }
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-        } else {
-            // Generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-            }
+        self.history.record(self.snapshot("Lock"));
+
+        if let Some(locked) = self.palette.toggle_lock(idx) {
+            let state = if locked { "locked" } else { "unlocked" };
+            self.notify(Level::Info, format!("Block {} {state}", idx + 1));
         }
+    }
 
-        for (i, block) in self.color_blocks.iter_mut().enumerate() {
-            if let Some(color_block) = block {
-                if !color_block.locked {
-                    let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
+    /// Adds a block `offset` places after the start of the selected one, so
+    /// `0` puts it before the selection and `1` after.
+    fn add_block(&mut self, offset: usize) {
+        self.history.record(self.snapshot("Add Block"));
+
+        let idx = self.palette.selected_index() + offset;
+
+        // halfway between its neighbours, or a copy at either end
+        let hsv = match (
+            idx.checked_sub(1).and_then(|i| self.palette.get(i)),
+            self.palette.get(idx),
+        ) {
+            (Some(before), Some(after)) => before.hsv.mix(after.hsv, 0.5),
+            (Some(block), None) | (None, Some(block)) => block.hsv,
+            (None, None) => Hsv::new(0.0, 0.0, 0.0),
+        };
 
-                    // Create triadic colors: base, base+120°, base+240°
-                    let new_hue = match i % 3 {
-                        0 => (base_hue + randomness) % 360.0,         // Primary
-                        1 => (base_hue + 120.0 + randomness) % 360.0, // First triad
-                        2 => (base_hue + 240.0 + randomness) % 360.0, // Second triad
-                        _ => unreachable!(),
-                    };
+        let mut block = ColorBlock::new(0.0, 0.0, 0.0);
+        block.hsv = hsv;
+        self.palette.insert(idx, block);
+    }
 
-                    let new_sat = if locked_blocks.is_empty() {
-                        rng.random_range(60..85) as f32 / 100.0 // Slightly higher saturation for vibrant triads
-                    } else {
-                        color_block.hsv.saturation
-                    };
+    fn move_block(&mut self, step: isize) {
+        let before = self.snapshot("Move Block");
 
-                    let new_val = if locked_blocks.is_empty() {
-                        rng.random_range(55..80) as f32 / 100.0
-                    } else {
-                        color_block.hsv.value
-                    };
+        if self.palette.move_selected(step) {
+            self.history.record(before);
+        }
+    }
 
-                    color_block.change_color(new_hue, new_sat, new_val);
-                }
+    // first press marks the selected block, the next swaps it with the selection
+    fn swap_block(&mut self) {
+        let selected = self.palette.selected_index();
+
+        match self.palette.marked() {
+            None => {
+                self.palette.mark_selected();
+                self.notify(
+                    Level::Info,
+                    format!("Block {} marked, select another to swap with", selected + 1),
+                );
+            }
+            Some(marked) if marked == selected => {
+                self.palette.clear_mark();
+                self.notify(Level::Info, "Swap cancelled");
+            }
+            Some(marked) => {
+                self.history.record(self.snapshot("Swap Blocks"));
+                self.palette.swap(marked, selected);
+                self.notify(
+                    Level::Info,
+                    format!("Swapped blocks {} and {}", marked + 1, selected + 1),
+                );
             }
         }
     }
 
-    fn generate_complementary(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let rand_rate = 15;
+    fn del_block(&mut self) {
+        self.history.record(self.snapshot("Delete Block"));
+
+        self.palette.remove_selected();
+    }
+
+    fn nudge_channel(&mut self, steps: f32, coarse: bool) {
+        if self.palette.selected().is_none() {
+            return;
+        }
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-        } else {
-            // Generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-            }
+        if !self.slider_recorded {
+            self.history.record(self.snapshot("Adjust Color"));
+            self.slider_recorded = true;
         }
 
-        for (i, block) in self.color_blocks.iter_mut().enumerate() {
-            if let Some(color_block) = block {
-                if !color_block.locked {
-                    let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
+        if let Some(block) = self.palette.selected_mut() {
+            block.hsv = self
+                .slider_mode
+                .nudge(block.hsv, self.slider_channel, steps, coarse);
+        }
+    }
 
-                    // Alternate between base hue and its complement
-                    let new_hue = if i % 2 == 0 {
-                        (base_hue + randomness) % 360.0
-                    } else {
-                        (base_hue + 180.0 + randomness) % 360.0
-                    };
+    fn generate(&mut self, seed: u64) {
+        if self.palette.iter().all(|block| block.locked) {
+            self.notify(Level::Warn, "Every block is locked");
+            return;
+        }
 
-                    let new_sat = if locked_blocks.is_empty() {
-                        rng.random_range(50..80) as f32 / 100.0
-                    } else {
-                        color_block.hsv.saturation
-                    };
+        self.history.record(self.snapshot("Generate"));
 
-                    let new_val = if locked_blocks.is_empty() {
-                        rng.random_range(50..80) as f32 / 100.0
-                    } else {
-                        color_block.hsv.value
-                    };
+        self.seed = Some(seed);
+        generator::generate(
+            self.theories.get_or_first(&self.current_color_theory),
+            self.color_space,
+            &mut self.palette,
+            seed,
+        );
+    }
 
-                    color_block.change_color(new_hue, new_sat, new_val);
-                }
-            }
+    fn snapshot(&self, label: &'static str) -> Snapshot {
+        Snapshot {
+            label,
+            palette: self.palette.clone(),
+            seed: self.seed,
         }
     }
 
-    fn generate_analogous(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let hue_step = 30.0; // standard analogous step
-        let rand_rate = 10;
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.palette = snapshot.palette;
+        self.palette.clear_mark();
+        self.seed = snapshot.seed;
+    }
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-        } else {
-            // generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
+    fn undo(&mut self) {
+        match self.history.undo(self.snapshot("")) {
+            Some(snapshot) => {
+                self.notify(Level::Info, format!("Undid {}", snapshot.label));
+                self.restore(snapshot);
             }
+            None => self.notify(Level::Warn, "Nothing to undo"),
         }
+    }
 
-        for (i, block) in self.color_blocks.iter_mut().enumerate() {
-            if let Some(color_block) = block {
-                if !color_block.locked {
-                    let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
-                    let new_hue = (base_hue + (i as f32 * hue_step) + randomness) % 360.0;
+    fn redo(&mut self) {
+        match self.history.redo(self.snapshot("")) {
+            Some(snapshot) => {
+                self.notify(Level::Info, format!("Redid {}", snapshot.label));
+                self.restore(snapshot);
+            }
+            None => self.notify(Level::Warn, "Nothing to redo"),
+        }
+    }
 
-                    let new_sat = if locked_blocks.is_empty() {
-                        rng.random_range(50..80) as f32 / 100.0
-                    } else {
-                        color_block.hsv.saturation
-                    };
+    fn open_export(&mut self, format: Option<ExportFormat>) {
+        if let Some(format) = format {
+            self.export_format = format;
+        }
 
-                    let new_val = if locked_blocks.is_empty() {
-                        rng.random_range(50..80) as f32 / 100.0
-                    } else {
-                        color_block.hsv.value
-                    };
+        self.export_block_id = 0;
+        self.export_message = None;
+        self.export_overwrite = None;
+        self.current_page = CurrentPage::Export;
+    }
 
-                    color_block.change_color(new_hue, new_sat, new_val);
-                }
-            }
+    fn export_block(&mut self) -> Option<&mut ColorBlock> {
+        self.palette.get_mut(self.export_block_id)
+    }
+
+    fn cycle_export_format(&mut self, step: isize) {
+        let formats: Vec<ExportFormat> = ExportFormat::iter().collect();
+        let idx = formats
+            .iter()
+            .position(|f| *f == self.export_format)
+            .unwrap_or(0);
+
+        self.export_format =
+            formats[(idx as isize + step).rem_euclid(formats.len() as isize) as usize];
+        self.export_message = None;
+    }
+
+    fn write_export(&mut self) {
+        let path = self.export_format.output_path(&self.save_file_field);
+
+        if path.exists() && self.export_overwrite.as_ref() != Some(&path) {
+            let key = self
+                .keymap
+                .key(Context::Export, Action::Confirm)
+                .map_or_else(String::new, |key| format!(", {key} again to overwrite"));
+
+            self.export_result(Err(format!("{} already exists{key}", path.display())));
+            self.export_overwrite = Some(path);
+            return;
         }
+
+        self.export_overwrite = None;
+        let bytes = export::export(self.export_format, &self.save_file_field, &self.palette);
+
+        self.export_result(
+            fs::write(&path, bytes)
+                .map(|_| format!("Wrote {}", path.display()))
+                .map_err(|err| format!("{}: {err}", path.display())),
+        );
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    // shown in the popup and the status bar, which stays after the popup closes
+    fn export_result(&mut self, result: Result<String, String>) {
+        match &result {
+            Ok(message) => self.notify(Level::Info, message.clone()),
+            Err(message) => self.notify(Level::Error, message.clone()),
+        }
+
+        self.export_message = Some(result);
+    }
+
+    fn save_palette(&mut self) {
+        let path = palette_file::palette_path(&self.save_file_field);
+        let mut file = PaletteFile::new(&self.palette, &self.current_color_theory);
+        file.seed = self.seed;
+        file.color_space = self.color_space;
+        file.tags = self
+            .save_tags_field
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches('#').trim())
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect();
+
+        match file.save(&path) {
+            Ok(()) => {
+                self.notify(Level::Info, format!("Saved {}", path.display()));
+                self.save_file_error = None;
+                self.current_page = CurrentPage::Main;
+            }
+            Err(err) => self.save_file_error = Some(err.to_string()),
+        }
     }
 
-    fn increment_counter(&mut self) {
-        self.selected_block_id = self
-            .selected_block_id
-            .saturating_add(1)
-            .clamp(0, self.color_block_count - 1);
+    fn open_file_picker(&mut self) {
+        let dir = palette_file::palette_dir();
+        // an empty library is fine, a missing one would just show an error
+        let _ = fs::create_dir_all(&dir);
+
+        self.file_picker = Some(FilePicker::new(
+            " Open Palette ",
+            dir,
+            &[PALETTE_FILE_EXTENSION],
+        ));
+        self.current_page = CurrentPage::OpenPalette;
     }
 
-    fn decrement_counter(&mut self) {
-        self.selected_block_id = self
-            .selected_block_id
-            .saturating_sub(1)
-            .clamp(0, self.color_block_count - 1);
+    fn open_import_picker(&mut self) {
+        let dir = env::current_dir().unwrap_or_else(|_| palette_file::palette_dir());
+
+        self.file_picker = Some(FilePicker::new(
+            " Import Palette ",
+            dir,
+            export::IMPORT_EXTENSIONS,
+        ));
+        self.current_page = CurrentPage::ImportPalette;
     }
 
-    fn toggle_lock(&mut self, id: usize) {
-        self.color_blocks[id - 1].as_mut().map(|color_block| {
-            color_block.locked = !color_block.locked;
-        });
+    fn import_palette(&mut self, path: &Path) {
+        match export::import(path) {
+            Ok(imported) => {
+                self.history.record(self.snapshot("Import"));
+
+                self.palette = ColorPalette::from_blocks(imported.color_blocks);
+                self.seed = None;
+
+                if imported.dropped > 0 {
+                    self.notify(
+                        Level::Warn,
+                        format!(
+                            "Imported {}, kept the first {MAX_BLOCKS} colors and dropped {}",
+                            path.display(),
+                            imported.dropped
+                        ),
+                    );
+                } else {
+                    self.notify(Level::Info, format!("Imported {}", path.display()));
+                }
+
+                self.save_file_field = imported.name;
+                self.save_tags_field.clear();
+
+                self.current_page = CurrentPage::Main;
+            }
+            Err(err) => {
+                self.notify(Level::Error, format!("Import failed: {err}"));
+
+                if let Some(file_picker) = self.file_picker.as_mut() {
+                    file_picker.error = Some(err);
+                }
+            }
+        }
     }
 
-    fn add_block(&mut self) {
-        if let Some(idx) = self.color_blocks.iter().position(|x| x.is_none()) {
-            self.color_blocks[idx] = Some(ColorBlock::new(idx, 0 as f32, 0 as f32, 0 as f32));
-            self.color_block_count += 1;
+    fn open_palette(&mut self, path: &Path) {
+        match PaletteFile::load(path) {
+            Ok(file) => {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                self.apply_palette_file(file, name);
+            }
+            Err(err) => {
+                self.notify(Level::Error, format!("Open failed: {err}"));
+
+                if let Some(file_picker) = self.file_picker.as_mut() {
+                    file_picker.error = Some(err.to_string());
+                }
+            }
         }
     }
 
-    fn del_block(&mut self) {
-        self.color_blocks[self.selected_block_id] = None;
-        self.color_block_count -= 1;
-        self.selected_block_id = 0;
+    fn apply_palette_file(&mut self, file: PaletteFile, name: String) {
+        self.history.record(self.snapshot("Open"));
+
+        self.palette = ColorPalette::from_blocks(file.color_blocks());
+        self.current_color_theory = file.theory;
+        self.seed = file.seed;
+        self.color_space = file.color_space;
+
+        self.notify(Level::Info, format!("Opened {name}"));
+
+        // so saving again overwrites the file it came from
+        self.save_file_field = name;
+        self.save_tags_field = file.tags.join(", ");
+
+        self.current_page = CurrentPage::Main;
     }
-}
 
-impl Default for App {
-    fn default() -> Self {
-        let color_block_count: usize = 5;
-        let mut color_blocks: [Option<ColorBlock>; 9] = [None; 9];
+    fn open_library(&mut self) {
+        self.library = Library::load(palette_file::palette_dir());
+        self.library_query.clear();
+        self.library_state.select_first();
+        self.current_page = CurrentPage::Library;
+    }
+}
 
-        for i in 1..color_block_count + 1 {
-            color_blocks[i - 1] = Some(ColorBlock::new(i, 0.0, 0.0, 0.0));
-        }
+impl App {
+    /// Starts from `config.toml`, or `config_path` when given. Problems with
+    /// any of the files end up in the status bar.
+    pub fn new(config_path: Option<&Path>) -> Self {
+        let mut theories = TheoryRegistry::default();
+        let theory_errors = custom::register_from(&mut theories, &custom::theories_path());
+        let (config, config_errors) = config::load(config_path, &mut theories);
+        let (keymap, keymap_errors) = keymap::load(&keymap::keymap_path());
+
+        let load_errors = [
+            config::summary(custom::THEORIES_FILE_NAME, &theory_errors),
+            config::summary(&config::file_name(config_path), &config_errors),
+            config::summary(keymap::KEYMAP_FILE_NAME, &keymap_errors),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("  ");
 
         Self {
             counter: 0,
@@ -493,18 +1318,55 @@ impl Default for App {
 
             theory_selector_state: ListState::default(),
             current_page: CurrentPage::Main,
-            current_color_theory: ColorTheories::Analogous,
+            theories,
+            current_color_theory: config.theory,
+            color_space: config.color_space,
+
+            keymap,
+            show_help: false,
+
+            show_contrast: false,
+            cvd_mode: CvdMode::default(),
+            color_depth: ColorDepth::default(),
 
             title: " Color Palette!!!!! ",
-            color_block_count: color_block_count,
-            selected_block_id: 0,
 
-            color_blocks: color_blocks,
+            palette: ColorPalette::new(config.blocks),
+            block_scroll: 0,
+
+            seed: None,
+            seed_field: String::new(),
+
+            history: History::default(),
+            history_state: ListState::default(),
+
+            export_format: ExportFormat::Css,
+            export_block_id: 0,
+            export_message: None,
+            export_overwrite: None,
 
-            status_bar_msg: "",
+            notification: (!load_errors.is_empty())
+                .then(|| Notification::new(Level::Warn, load_errors)),
 
             edit_color_field: String::new(),
 
+            slider_mode: SliderMode::default(),
+            slider_channel: 0,
+            slider_recorded: false,
+
+            frame_area: Rect::default(),
+            scrolled_block: None,
+
+            save_file_field: String::new(),
+            save_tags_field: String::new(),
+            save_tags_focused: false,
+            save_file_error: None,
+            file_picker: None,
+
+            library: Library::default(),
+            library_query: String::new(),
+            library_state: ListState::default(),
+
             exit: false,
         }
     }
@@ -512,18 +1374,36 @@ impl Default for App {
 
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        // SELECTED BLOCK
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Fill(1), Constraint::Length(3)])
-            .split(area);
+        let (main_area, contrast_area, footer_area) = self.split_screen(area);
 
-        let (main_area, footer_area) = (layout[0], layout[1]);
+        if let Some(contrast_area) = contrast_area {
+            ContrastMatrix::new(&self.palette).render(contrast_area, buf);
+        }
 
-        let mut main_content = MainContent::new(self.color_blocks, self.selected_block_id);
+        let mut main_content = MainContent::new(
+            self.palette.to_vec(),
+            self.palette.selected_index(),
+            self.palette.marked(),
+            self.block_scroll,
+            self.cvd_mode,
+            self.color_depth,
+        );
         main_content.render(main_area, buf);
 
-        let status_bar = StatusBar::default();
+        let status_bar = StatusBar {
+            notification: self
+                .notification
+                .as_ref()
+                .filter(|notification| !notification.is_expired()),
+            page: self.current_page.title(),
+            theory: &self.current_color_theory,
+            block_count: self.palette.len(),
+            hints: &self.keymap.hints(self.current_page.context()),
+            seed: self.seed,
+            color_space: self.color_space,
+            cvd: self.cvd_mode,
+            color_depth: self.color_depth,
+        };
         status_bar.render(footer_area, buf);
     }
 }
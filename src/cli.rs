@@ -0,0 +1,261 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::{
+    color_depth::ColorDepth,
+    color_palette::{ColorPalette, MAX_BLOCKS, MIN_BLOCKS},
+    config::{self, Config},
+    export::{self, ExportFormat},
+    generator::{self, ColorSpace},
+    palette_file::PaletteFile,
+    theory::{ColorTheory, TheoryRegistry, custom},
+};
+
+/// Color palette generator.
+///
+/// Starts the interactive editor unless a subcommand is given.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Colors the terminal can show, detected from COLORTERM and TERM when not given
+    #[arg(long, value_enum)]
+    pub colors: Option<ColorDepth>,
+
+    /// Read defaults from this file instead of ~/.config/terminal-palette/config.toml
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate a palette and print it to stdout without starting the UI
+    Generate(GenerateArgs),
+    /// Convert a saved palette to CSS, SCSS, a Tailwind config, GIMP or Adobe swatches
+    Export(ExportArgs),
+    /// Convert a GIMP (.gpl) or Adobe (.ase) palette to a palette file
+    Import(ImportArgs),
+    /// Print the configuration in use, every default filled in, as a config.toml
+    Config,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct GenerateArgs {
+    /// Theory name, e.g. analogous, triad or split-complementary [default: from the config]
+    #[arg(short, long)]
+    pub theory: Option<String>,
+
+    /// Number of colors in the palette [default: from the config]
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(MIN_BLOCKS as i64..=MAX_BLOCKS as i64))]
+    pub count: Option<u8>,
+
+    /// Color space hues are rotated in [default: from the config]
+    #[arg(long, value_enum)]
+    pub space: Option<ColorSpace>,
+
+    /// Seed for the random generator, the same seed and theory always give the same palette
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Hex)]
+    pub format: OutputFormat,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExportArgs {
+    /// Palette file saved from the editor
+    pub palette: PathBuf,
+
+    #[arg(short, long, value_enum)]
+    pub format: ExportFormat,
+
+    /// Comma separated names for the colors, in palette order. Overrides the names in the file
+    #[arg(short, long, value_delimiter = ',')]
+    pub names: Vec<String>,
+
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ImportArgs {
+    pub file: PathBuf,
+
+    /// Theory stored with the palette [default: from the config]
+    #[arg(short, long)]
+    pub theory: Option<String>,
+
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// One `#RRGGBB` per line
+    Hex,
+    /// One `r, g, b` per line
+    Rgb,
+    /// One `h, s, v` per line
+    Hsv,
+    /// A palette file that can be opened in the UI
+    Toml,
+}
+
+pub fn run(command: Command, config_path: Option<&Path>) -> io::Result<()> {
+    match command {
+        Command::Generate(args) => generate(args, config_path),
+        Command::Export(args) => export(args),
+        Command::Import(args) => import(args, config_path),
+        Command::Config => print_config(config_path),
+    }
+}
+
+/// The theories and the config, with problems printed as warnings.
+fn load_config(config_path: Option<&Path>) -> (Config, TheoryRegistry) {
+    let mut theories = TheoryRegistry::default();
+    for err in custom::register_from(&mut theories, &custom::theories_path()) {
+        eprintln!("warning: {}: {err}", custom::THEORIES_FILE_NAME);
+    }
+
+    let (config, errors) = config::load(config_path, &mut theories);
+    // the file that was actually read, so warnings point at it
+    let config_file = config_path.map_or_else(config::config_path, Path::to_path_buf);
+    for err in errors {
+        eprintln!("warning: {}: {err}", config_file.display());
+    }
+
+    (config, theories)
+}
+
+fn print_config(config_path: Option<&Path>) -> io::Result<()> {
+    let (config, theories) = load_config(config_path);
+
+    write!(
+        io::stdout().lock(),
+        "{}",
+        config::to_toml(&config, &theories)
+    )
+}
+
+/// Looks `name` up, listing the known theories when it isn't one.
+fn find_theory<'a>(theories: &'a TheoryRegistry, name: &str) -> io::Result<&'a dyn ColorTheory> {
+    theories.get(name).ok_or_else(|| {
+        io::Error::other(format!(
+            "unknown theory \"{name}\", expected one of: {}",
+            theories.names().join(", ")
+        ))
+    })
+}
+
+fn import(args: ImportArgs, config_path: Option<&Path>) -> io::Result<()> {
+    let (config, theories) = load_config(config_path);
+    let theory = find_theory(&theories, args.theory.as_deref().unwrap_or(&config.theory))?;
+
+    let imported = export::import(&args.file)
+        .map_err(|err| io::Error::other(format!("{}: {err}", args.file.display())))?;
+
+    if imported.dropped > 0 {
+        eprintln!(
+            "warning: {}: kept the first {MAX_BLOCKS} colors, dropped {}",
+            args.file.display(),
+            imported.dropped
+        );
+    }
+
+    let mut file = PaletteFile::new(&imported.color_blocks, theory.name());
+    file.color_space = config.color_space;
+
+    match args.output {
+        Some(path) => file
+            .save(&path)
+            .map_err(|err| io::Error::other(err.to_string())),
+        None => {
+            let text = file
+                .to_toml()
+                .map_err(|err| io::Error::other(err.to_string()))?;
+            write!(io::stdout().lock(), "{text}")
+        }
+    }
+}
+
+fn export(args: ExportArgs) -> io::Result<()> {
+    let file = PaletteFile::load(&args.palette)
+        .map_err(|err| io::Error::other(format!("{}: {err}", args.palette.display())))?;
+
+    let mut color_blocks = file.color_blocks();
+
+    for (block, name) in color_blocks.iter_mut().zip(args.names.iter()) {
+        block.name = name.clone();
+    }
+
+    let name = args
+        .palette
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let text = export::export(args.format, &name, &color_blocks);
+
+    match args.output {
+        Some(path) => fs::write(path, text),
+        None => io::stdout().lock().write_all(&text),
+    }
+}
+
+fn generate(args: GenerateArgs, config_path: Option<&Path>) -> io::Result<()> {
+    let (config, theories) = load_config(config_path);
+
+    let count = args.count.map_or(config.blocks, usize::from);
+    let space = args.space.unwrap_or(config.color_space);
+    let theory_name = args.theory.unwrap_or(config.theory);
+
+    let mut color_blocks = ColorPalette::new(count);
+
+    let theory = find_theory(&theories, &theory_name)?;
+
+    let seed = args.seed.unwrap_or_else(generator::random_seed);
+    generator::generate(theory, space, &mut color_blocks, seed);
+
+    // stderr so piping the palette somewhere keeps working
+    if args.seed.is_none() {
+        eprintln!("seed: {seed}");
+    }
+
+    let mut stdout = io::stdout().lock();
+
+    if args.format == OutputFormat::Toml {
+        let mut file = PaletteFile::new(&color_blocks, theory.name());
+        file.seed = Some(seed);
+        file.color_space = space;
+        let text = file
+            .to_toml()
+            .map_err(|err| io::Error::other(err.to_string()))?;
+
+        return write!(stdout, "{text}");
+    }
+
+    for block in color_blocks.iter() {
+        match args.format {
+            OutputFormat::Hex => writeln!(stdout, "{}", block.get_hex())?,
+            OutputFormat::Rgb => {
+                let (r, g, b) = block.get_rgb_values();
+                writeln!(stdout, "{r}, {g}, {b}")?
+            }
+            OutputFormat::Hsv => {
+                let (h, s, v) = block.get_hsv_values();
+                writeln!(stdout, "{:.1}, {s:.3}, {v:.3}", h.rem_euclid(360.0))?
+            }
+            OutputFormat::Toml => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
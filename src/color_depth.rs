@@ -0,0 +1,177 @@
+//! How many colors the terminal can show, and squeezing RGB colors into
+//! the xterm 256 and 16 color palettes when it can't do truecolor.
+
+use std::{collections::HashMap, env, sync::OnceLock};
+
+use clap::ValueEnum;
+use palette::{IntoColor, Oklab, Srgb};
+use ratatui::{buffer::Buffer, style::Color};
+
+use crate::contrast::Rgb;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, ValueEnum)]
+pub enum ColorDepth {
+    /// 24 bit RGB
+    #[default]
+    #[value(name = "truecolor")]
+    TrueColor,
+    /// The xterm 256 color palette
+    #[value(name = "256")]
+    Ansi256,
+    /// The 16 ANSI colors, e.g. the Linux console
+    #[value(name = "16")]
+    Ansi16,
+}
+
+// xterm's defaults, terminals with a custom theme will show something else
+const ANSI_16: [Rgb; 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+// the named variants go out as SGR 30-37/90-97, which even the Linux console understands
+const ANSI_16_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The RGB value of an xterm-256 index.
+fn xterm_rgb(index: u8) -> Rgb {
+    match index {
+        0..=15 => ANSI_16[index as usize],
+        16..=231 => {
+            let i = (index - 16) as usize;
+            (
+                CUBE_LEVELS[i / 36],
+                CUBE_LEVELS[(i / 6) % 6],
+                CUBE_LEVELS[i % 6],
+            )
+        }
+        232..=255 => {
+            let gray = 8 + 10 * (index - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+fn oklab((r, g, b): Rgb) -> Oklab {
+    Srgb::new(r, g, b)
+        .into_format::<f32>()
+        .into_linear()
+        .into_color()
+}
+
+// candidate indices with their OKLab values, so matching is perceptual
+fn candidates(depth: ColorDepth) -> &'static [(u8, Oklab)] {
+    static ANSI_256_LAB: OnceLock<Vec<(u8, Oklab)>> = OnceLock::new();
+    static ANSI_16_LAB: OnceLock<Vec<(u8, Oklab)>> = OnceLock::new();
+
+    match depth {
+        ColorDepth::TrueColor => &[],
+        // 0-15 are left out, themes change them
+        ColorDepth::Ansi256 => {
+            ANSI_256_LAB.get_or_init(|| (16..=255).map(|i| (i, oklab(xterm_rgb(i)))).collect())
+        }
+        ColorDepth::Ansi16 => {
+            ANSI_16_LAB.get_or_init(|| (0..16).map(|i| (i, oklab(xterm_rgb(i)))).collect())
+        }
+    }
+}
+
+impl ColorDepth {
+    /// Reads `COLORTERM` and `TERM` the way most terminal programs do.
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default().to_lowercase();
+        let term = env::var("TERM").unwrap_or_default().to_lowercase();
+
+        if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorDepth::TrueColor => "truecolor",
+            ColorDepth::Ansi256 => "256 colors",
+            ColorDepth::Ansi16 => "16 colors",
+        }
+    }
+
+    /// Closest palette index for `rgb`, `None` in truecolor.
+    pub fn nearest_index(self, rgb: Rgb) -> Option<u8> {
+        let target = oklab(rgb);
+
+        candidates(self)
+            .iter()
+            .min_by(|(_, a), (_, b)| distance(*a, target).total_cmp(&distance(*b, target)))
+            .map(|(index, _)| *index)
+    }
+
+    /// What `Color::Rgb(rgb)` should be drawn as.
+    pub fn quantize(self, rgb: Rgb) -> Color {
+        match (self, self.nearest_index(rgb)) {
+            (ColorDepth::Ansi16, Some(index)) => ANSI_16_COLORS[index as usize],
+            (_, Some(index)) => Color::Indexed(index),
+            (_, None) => Color::Rgb(rgb.0, rgb.1, rgb.2),
+        }
+    }
+
+    /// Replaces every RGB color in the frame, run after everything is drawn.
+    pub fn quantize_buffer(self, buf: &mut Buffer) {
+        if self == ColorDepth::TrueColor {
+            return;
+        }
+
+        // frames only have a handful of distinct colors
+        let mut cache: HashMap<Rgb, Color> = HashMap::new();
+        let mut convert = |color: Color| match color {
+            Color::Rgb(r, g, b) => *cache
+                .entry((r, g, b))
+                .or_insert_with(|| self.quantize((r, g, b))),
+            other => other,
+        };
+
+        for cell in buf.content.iter_mut() {
+            cell.fg = convert(cell.fg);
+            cell.bg = convert(cell.bg);
+        }
+    }
+}
+
+fn distance(a: Oklab, b: Oklab) -> f32 {
+    (a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)
+}
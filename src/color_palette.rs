@@ -0,0 +1,168 @@
+//! The blocks of the palette being edited, in order, and which one is
+//! selected. Blocks are always packed, so an index means the same block to
+//! the screen, the key handler and the files.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::widgets::content::ColorBlock;
+
+/// Fewest blocks a palette can be cut down to.
+pub const MIN_BLOCKS: usize = 3;
+/// Most blocks a palette can hold, the ones that don't fit on screen scroll.
+pub const MAX_BLOCKS: usize = 32;
+
+#[derive(Clone, Debug, Default)]
+pub struct ColorPalette {
+    blocks: Vec<ColorBlock>,
+    selected: usize,
+    // first half of a swap, cleared whenever blocks are added, removed or moved
+    marked: Option<usize>,
+}
+
+impl ColorPalette {
+    /// `count` black blocks.
+    pub fn new(count: usize) -> Self {
+        Self::from_blocks((0..count).map(|_| ColorBlock::new(0.0, 0.0, 0.0)).collect())
+    }
+
+    /// Anything past [`MAX_BLOCKS`] is dropped.
+    pub fn from_blocks(mut blocks: Vec<ColorBlock>) -> Self {
+        blocks.truncate(MAX_BLOCKS);
+
+        Self {
+            blocks,
+            selected: 0,
+            marked: None,
+        }
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected(&self) -> Option<&ColorBlock> {
+        self.blocks.get(self.selected)
+    }
+
+    pub fn selected_mut(&mut self) -> Option<&mut ColorBlock> {
+        self.blocks.get_mut(self.selected)
+    }
+
+    /// Selects `index`, or the nearest block when it's past the end.
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.blocks.len().saturating_sub(1));
+    }
+
+    pub fn select_next(&mut self) {
+        self.select(self.selected + 1);
+    }
+
+    pub fn select_previous(&mut self) {
+        self.select(self.selected.saturating_sub(1));
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.blocks.len() >= MAX_BLOCKS
+    }
+
+    pub fn can_remove(&self) -> bool {
+        self.blocks.len() > MIN_BLOCKS
+    }
+
+    pub fn marked(&self) -> Option<usize> {
+        self.marked
+    }
+
+    /// Marks the selected block as the first half of a swap.
+    pub fn mark_selected(&mut self) {
+        self.marked = Some(self.selected);
+    }
+
+    pub fn clear_mark(&mut self) {
+        self.marked = None;
+    }
+
+    /// Inserts `block` before `index` (at the end when past it) and selects it.
+    pub fn insert(&mut self, index: usize, block: ColorBlock) {
+        if self.is_full() {
+            return;
+        }
+
+        let index = index.min(self.blocks.len());
+        self.blocks.insert(index, block);
+        self.selected = index;
+        self.marked = None;
+    }
+
+    /// Inserts a copy of the selected block right after it and selects the copy.
+    pub fn duplicate_selected(&mut self) {
+        if let Some(block) = self.selected().cloned() {
+            self.insert(self.selected + 1, block);
+        }
+    }
+
+    /// Removes the selected block, the one after it takes its place.
+    pub fn remove_selected(&mut self) -> Option<ColorBlock> {
+        if !self.can_remove() {
+            return None;
+        }
+
+        let block = self.blocks.remove(self.selected);
+        self.select(self.selected);
+        self.marked = None;
+
+        Some(block)
+    }
+
+    /// Moves the selected block one place left (`-1`) or right (`1`), the
+    /// selection goes with it. Returns `false` at either end.
+    pub fn move_selected(&mut self, step: isize) -> bool {
+        let Some(target) = self
+            .selected
+            .checked_add_signed(step)
+            .filter(|target| *target < self.blocks.len())
+        else {
+            return false;
+        };
+
+        self.blocks.swap(self.selected, target);
+        self.selected = target;
+        self.marked = None;
+
+        true
+    }
+
+    /// Swaps the blocks at `a` and `b`, the selection stays where it is.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a < self.blocks.len() && b < self.blocks.len() {
+            self.blocks.swap(a, b);
+        }
+
+        self.marked = None;
+    }
+
+    /// Flips the lock of the block at `index`, returning whether it's now
+    /// locked, or `None` if there is no such block.
+    pub fn toggle_lock(&mut self, index: usize) -> Option<bool> {
+        let block = self.blocks.get_mut(index)?;
+        block.locked = !block.locked;
+
+        Some(block.locked)
+    }
+}
+
+// reading and recoloring blocks goes through the slice, only adding and
+// removing them needs the methods above
+impl Deref for ColorPalette {
+    type Target = [ColorBlock];
+
+    fn deref(&self) -> &[ColorBlock] {
+        &self.blocks
+    }
+}
+
+impl DerefMut for ColorPalette {
+    fn deref_mut(&mut self) -> &mut [ColorBlock] {
+        &mut self.blocks
+    }
+}
@@ -0,0 +1,324 @@
+//! Parses what the user types into the Edit Color popup.
+//!
+//! Accepted forms, case-insensitive:
+//!
+//! ```text
+//! #rgb  #rgba  #rrggbb  #rrggbbaa   (the '#' is optional)
+//! rgb(255 128 0)  rgb(100%, 50%, 0%)  rgba(255, 128, 0, 0.5)
+//! hsl(30 100% 50%)  hsl(30deg, 100%, 50%)
+//! hsv(30 100% 100%)  hsv(30, 1.0, 1.0)
+//! oklch(0.7 0.15 60)  oklch(70% 0.15 60deg)
+//! lab(65 40 70)
+//! orange  rebeccapurple  (CSS named colors)
+//! ```
+//!
+//! Functions take an optional alpha after a `/` or as a fourth argument.
+//! Alpha is parsed so pasted colors work, but blocks are always opaque.
+
+mod named;
+
+use palette::{
+    FromColor, Hsv, IsWithinBounds, Lab, Oklch, Srgb, Xyz,
+    chromatic_adaptation::AdaptIntoUnclamped,
+    convert::FromColorUnclamped,
+    white_point::{D50, D65},
+};
+
+use crate::theory::gamut_map;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ParsedColor {
+    pub rgb: Srgb<f32>,
+    pub alpha: f32,
+    /// The color was outside sRGB and had to be brought in.
+    pub clipped: bool,
+}
+
+impl ParsedColor {
+    fn opaque(rgb: Srgb<f32>) -> Self {
+        Self {
+            rgb,
+            alpha: 1.0,
+            clipped: false,
+        }
+    }
+
+    pub fn hsv(&self) -> Hsv {
+        Hsv::from_color(self.rgb)
+    }
+
+    pub fn rgb8(&self) -> (u8, u8, u8) {
+        let rgb: Srgb<u8> = self.rgb.into_format();
+        (rgb.red, rgb.green, rgb.blue)
+    }
+}
+
+pub fn parse(input: &str) -> Result<ParsedColor, String> {
+    let input = input.trim().to_lowercase();
+
+    if input.is_empty() {
+        return Err(String::from("type a color"));
+    }
+
+    if let Some((function, rest)) = input.split_once('(') {
+        let body = rest
+            .strip_suffix(')')
+            .ok_or_else(|| format!("missing ')' after {}(", function.trim()))?;
+
+        return parse_function(function.trim(), body);
+    }
+
+    if let Some(rgb) = named::lookup(&input) {
+        return Ok(ParsedColor::opaque(from_u8(rgb)));
+    }
+
+    match input.strip_prefix('#') {
+        Some(hex) => parse_hex(hex),
+        None if input.chars().all(|c| c.is_ascii_hexdigit()) => parse_hex(&input),
+        None => Err(format!("unknown color name \"{input}\"")),
+    }
+}
+
+fn from_u8((r, g, b): (u8, u8, u8)) -> Srgb<f32> {
+    Srgb::new(r, g, b).into_format()
+}
+
+fn parse_hex(hex: &str) -> Result<ParsedColor, String> {
+    if let Some(bad) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("'{bad}' is not a hex digit"));
+    }
+
+    // #rgb and #rgba repeat every digit
+    let expanded: String = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        len => return Err(format!("hex colors have 3, 4, 6 or 8 digits, not {len}")),
+    };
+
+    let byte = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).unwrap();
+    let alpha = if expanded.len() == 8 {
+        byte(6) as f32 / 255.0
+    } else {
+        1.0
+    };
+
+    Ok(ParsedColor {
+        alpha,
+        ..ParsedColor::opaque(from_u8((byte(0), byte(2), byte(4))))
+    })
+}
+
+/// Splits `a b c / d` or `a, b, c, d` into the three components and alpha.
+fn split_args(body: &str) -> Result<([&str; 3], Option<&str>), String> {
+    let (components, alpha) = match body.split_once('/') {
+        Some((components, alpha)) => (components, Some(alpha.trim())),
+        None => (body, None),
+    };
+
+    let mut parts: Vec<&str> = components
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    let alpha = match (alpha, parts.len()) {
+        (Some(alpha), 3) => Some(alpha),
+        (None, 4) => parts.pop(),
+        (None, 3) => None,
+        (_, count) => {
+            return Err(format!(
+                "expected 3 values and an optional alpha, got {count}"
+            ));
+        }
+    };
+
+    Ok(([parts[0], parts[1], parts[2]], alpha))
+}
+
+fn number(token: &str) -> Result<f32, String> {
+    token
+        .parse::<f32>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or_else(|| format!("\"{token}\" is not a number"))
+}
+
+/// A plain number, or a percentage of `full`.
+fn scaled(token: &str, full: f32) -> Result<f32, String> {
+    match token.strip_suffix('%') {
+        Some(percent) => Ok(number(percent)? / 100.0 * full),
+        None => number(token),
+    }
+}
+
+/// Degrees, or another CSS angle unit.
+fn angle(token: &str) -> Result<f32, String> {
+    let degrees = if let Some(n) = token.strip_suffix("deg") {
+        number(n)?
+    } else if let Some(n) = token.strip_suffix("grad") {
+        number(n)? * 0.9
+    } else if let Some(n) = token.strip_suffix("rad") {
+        number(n)?.to_degrees()
+    } else if let Some(n) = token.strip_suffix("turn") {
+        number(n)? * 360.0
+    } else {
+        number(token)?
+    };
+
+    Ok(degrees.rem_euclid(360.0))
+}
+
+fn in_range(what: &str, value: f32, low: f32, high: f32) -> Result<f32, String> {
+    if (low..=high).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("{what} {value} is outside {low}..{high}"))
+    }
+}
+
+fn parse_alpha(token: Option<&str>) -> Result<f32, String> {
+    match token {
+        Some(token) => in_range("alpha", scaled(token, 1.0)?, 0.0, 1.0),
+        None => Ok(1.0),
+    }
+}
+
+/// HSV/HSL saturation and value: `50%`, or a fraction like `0.5` as the
+/// editor displays them, or a bare percentage like `50`.
+fn fraction(what: &str, token: &str) -> Result<f32, String> {
+    let value = match token.strip_suffix('%') {
+        Some(percent) => number(percent)? / 100.0,
+        None => {
+            let n = number(token)?;
+            if n > 1.0 { n / 100.0 } else { n }
+        }
+    };
+
+    in_range(what, value, 0.0, 1.0)
+}
+
+fn parse_function(function: &str, body: &str) -> Result<ParsedColor, String> {
+    let ([a, b, c], alpha) = split_args(body).map_err(|err| format!("{function}(): {err}"))?;
+    let alpha = parse_alpha(alpha)?;
+
+    let parsed = match function {
+        "rgb" | "rgba" => {
+            let red = in_range("red", scaled(a, 255.0)?, 0.0, 255.0)?;
+            let green = in_range("green", scaled(b, 255.0)?, 0.0, 255.0)?;
+            let blue = in_range("blue", scaled(c, 255.0)?, 0.0, 255.0)?;
+
+            ParsedColor::opaque(Srgb::new(red / 255.0, green / 255.0, blue / 255.0))
+        }
+        "hsl" | "hsla" => {
+            let hsl = palette::Hsl::new(
+                angle(a)?,
+                fraction("saturation", b)?,
+                fraction("lightness", c)?,
+            );
+
+            ParsedColor::opaque(Srgb::from_color(hsl))
+        }
+        "hsv" | "hsva" => {
+            let hsv = Hsv::new(angle(a)?, fraction("saturation", b)?, fraction("value", c)?);
+
+            ParsedColor::opaque(Srgb::from_color(hsv))
+        }
+        "oklch" => {
+            let lightness = in_range("lightness", scaled(a, 1.0)?, 0.0, 1.0)?;
+            // 100% chroma is 0.4 in CSS
+            let chroma = in_range("chroma", scaled(b, 0.4)?, 0.0, 0.5)?;
+            let oklch = Oklch::new(lightness, chroma, angle(c)?);
+
+            ParsedColor {
+                clipped: !Srgb::from_color_unclamped(oklch).is_within_bounds(),
+                ..ParsedColor::opaque(gamut_map(oklch))
+            }
+        }
+        "lab" => {
+            let lightness = in_range("lightness", scaled(a, 100.0)?, 0.0, 100.0)?;
+            // 100% is 125 for a and b in CSS
+            let green_red = in_range("a", scaled(b, 125.0)?, -160.0, 160.0)?;
+            let blue_yellow = in_range("b", scaled(c, 125.0)?, -160.0, 160.0)?;
+
+            // CSS lab() is relative to D50, like the ASE files
+            let xyz =
+                Xyz::<D50, f32>::from_color_unclamped(Lab::new(lightness, green_red, blue_yellow));
+            let xyz: Xyz<D65, f32> = xyz.adapt_into_unclamped();
+            let exact = Srgb::from_color_unclamped(xyz);
+            let clamped = Srgb::new(
+                exact.red.clamp(0.0, 1.0),
+                exact.green.clamp(0.0, 1.0),
+                exact.blue.clamp(0.0, 1.0),
+            );
+
+            ParsedColor {
+                clipped: !exact.is_within_bounds(),
+                ..ParsedColor::opaque(clamped)
+            }
+        }
+        _ => return Err(format!("unknown function {function}()")),
+    };
+
+    Ok(ParsedColor { alpha, ..parsed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb8(input: &str) -> (u8, u8, u8) {
+        parse(input).unwrap().rgb8()
+    }
+
+    #[test]
+    fn parses_every_syntax() {
+        assert_eq!(rgb8("#1e90ff"), (30, 144, 255));
+        assert_eq!(rgb8("1E90FF"), (30, 144, 255));
+        assert_eq!(rgb8("#f0a"), (255, 0, 170));
+        assert_eq!(rgb8("azure"), (240, 255, 255));
+        assert_eq!(rgb8("rgb(30, 144, 255)"), (30, 144, 255));
+        assert_eq!(rgb8("rgb(100% 0% 0%)"), (255, 0, 0));
+        assert_eq!(rgb8("hsl(120deg 100% 50%)"), (0, 255, 0));
+        assert_eq!(rgb8("hsv(0.5turn, 1, 1)"), (0, 255, 255));
+
+        let (r, g, b) = rgb8("lab(50% 0 0)");
+        assert!(r == g && g == b && (118..=120).contains(&r), "{r} {g} {b}");
+    }
+
+    #[test]
+    fn alpha_and_clipping() {
+        let parsed = parse("#ff000080").unwrap();
+        assert!((parsed.alpha - 128.0 / 255.0).abs() < 1e-6);
+
+        assert_eq!(parse("rgb(0 0 0 / 50%)").unwrap().alpha, 0.5);
+        assert_eq!(parse("rgba(0, 0, 0, 0.25)").unwrap().alpha, 0.25);
+
+        assert!(parse("oklch(0.7 0.4 150)").unwrap().clipped);
+        assert!(!parse("oklch(0.5 0 0)").unwrap().clipped);
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("", "type a color"),
+            ("  ", "type a color"),
+            ("notacolor", "unknown color name \"notacolor\""),
+            ("#12", "hex colors have 3, 4, 6 or 8 digits, not 2"),
+            ("#12345g", "'g' is not a hex digit"),
+            ("rgb(1, 2, 3", "missing ')' after rgb("),
+            (
+                "rgb(1, 2)",
+                "rgb(): expected 3 values and an optional alpha, got 2",
+            ),
+            ("rgb(1 2 x)", "\"x\" is not a number"),
+            ("rgb(300 0 0)", "red 300 is outside 0..255"),
+            ("rgb(0 0 0 / 2)", "alpha 2 is outside 0..1"),
+            ("hsl(0 150% 50%)", "saturation 1.5 is outside 0..1"),
+            ("lab(50 200 0)", "a 200 is outside -160..160"),
+            ("cmyk(0 0 0)", "unknown function cmyk()"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse(input).err().as_deref(), Some(expected), "{input:?}");
+        }
+    }
+}
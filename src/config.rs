@@ -0,0 +1,374 @@
+//! Defaults from `config.toml`, all keys optional:
+//!
+//! ```toml
+//! blocks = 5                  # colors in a new palette, 3 to 32
+//! theory = "Analogous"        # theory new palettes start with
+//! color_space = "hsv"         # or "oklch"
+//!
+//! [theories.analogous]        # any theory, built in or from theories.toml
+//! sat = "0.5..0.8"
+//! val = "0.5..0.8"
+//! randomness = 10
+//! jitter = 0.08
+//! ```
+//!
+//! Broken keys are reported and keep their default, the rest still apply.
+//! `terminal-palette config` prints what ends up being used.
+
+use std::{
+    env, fmt,
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use toml::Value;
+
+use crate::{
+    color_palette::{MAX_BLOCKS, MIN_BLOCKS},
+    generator::ColorSpace,
+    theory::{DEFAULT_THEORY, TheoryRegistry, custom},
+};
+
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// A problem in one of the files in [`config_dir`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    /// Dotted path to the offending key, e.g. `theory[1].sat`.
+    pub key: String,
+    pub message: String,
+}
+
+impl ConfigError {
+    pub fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.key, self.message)
+        }
+    }
+}
+
+/// One line for the status bar, `None` if everything loaded.
+pub fn summary(file_name: &str, errors: &[ConfigError]) -> Option<String> {
+    let first = errors.first()?;
+    let mut line = format!("{file_name}: {first}");
+
+    if errors.len() > 1 {
+        line.push_str(&format!(" (+{} more)", errors.len() - 1));
+    }
+
+    Some(line)
+}
+
+/// `$XDG_CONFIG_HOME/terminal-palette`, falling back to `~/.config`.
+pub fn config_dir() -> PathBuf {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    config_dir.join("terminal-palette")
+}
+
+pub fn config_path() -> PathBuf {
+    config_dir().join(CONFIG_FILE_NAME)
+}
+
+/// What warnings call the file: `config.toml`, or the `--config` path as given.
+pub fn file_name(path: Option<&Path>) -> String {
+    path.map_or_else(
+        || String::from(CONFIG_FILE_NAME),
+        |path| path.display().to_string(),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub blocks: usize,
+    pub theory: String,
+    pub color_space: ColorSpace,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            blocks: 5,
+            theory: String::from(DEFAULT_THEORY),
+            color_space: ColorSpace::default(),
+        }
+    }
+}
+
+/// Reads the config from `text`. `[theories.*]` tables are applied to
+/// `theories` right away, so custom theories have to be registered first.
+pub fn parse(text: &str, theories: &mut TheoryRegistry) -> (Config, Vec<ConfigError>) {
+    let mut config = Config::default();
+
+    let table: toml::Table = match toml::from_str(text) {
+        Ok(table) => table,
+        Err(err) => return (config, vec![ConfigError::new("", err.message())]),
+    };
+
+    let mut errors = Vec::new();
+
+    for (key, value) in &table {
+        match key.as_str() {
+            "blocks" => match value.as_integer() {
+                Some(blocks) if (MIN_BLOCKS as i64..=MAX_BLOCKS as i64).contains(&blocks) => {
+                    config.blocks = blocks as usize
+                }
+                _ => errors.push(ConfigError::new(
+                    key,
+                    format!("expected a number from {MIN_BLOCKS} to {MAX_BLOCKS}"),
+                )),
+            },
+            "theory" => match value.as_str().map(|name| theories.get(name)) {
+                Some(Some(theory)) => config.theory = theory.name().to_string(),
+                Some(None) => errors.push(ConfigError::new(
+                    key,
+                    format!(
+                        "unknown theory, expected one of: {}",
+                        theories.names().join(", ")
+                    ),
+                )),
+                None => errors.push(ConfigError::new(key, "expected a theory name")),
+            },
+            "color_space" => match value.as_str() {
+                Some("hsv") => config.color_space = ColorSpace::Hsv,
+                Some("oklch") => config.color_space = ColorSpace::Oklch,
+                _ => errors.push(ConfigError::new(key, "expected \"hsv\" or \"oklch\"")),
+            },
+            "theories" => match value {
+                Value::Table(overrides) => {
+                    errors.extend(apply_overrides(overrides, theories));
+                }
+                _ => errors.push(ConfigError::new(key, "expected [theories.<name>] tables")),
+            },
+            _ => errors.push(ConfigError::new(key, "unknown key")),
+        }
+    }
+
+    (config, errors)
+}
+
+fn apply_overrides(overrides: &toml::Table, theories: &mut TheoryRegistry) -> Vec<ConfigError> {
+    let mut errors = Vec::new();
+
+    for (name, value) in overrides {
+        let key = format!("theories.{name}");
+
+        let Value::Table(table) = value else {
+            errors.push(ConfigError::new(key, "expected a table"));
+            continue;
+        };
+
+        let Some(theory) = theories.get_mut(name) else {
+            errors.push(ConfigError::new(key, "unknown theory"));
+            continue;
+        };
+
+        if let Some(unknown) = table
+            .keys()
+            .find(|k| !custom::PARAMETER_KEYS.contains(&k.as_str()))
+        {
+            errors.push(ConfigError::new(format!("{key}.{unknown}"), "unknown key"));
+            continue;
+        }
+
+        // all or nothing, so a typo doesn't leave a theory half changed
+        let mut parameters = theory.parameters().clone();
+        match custom::parse_parameters(table, &key, &mut parameters) {
+            Ok(()) => *theory.parameters_mut() = parameters,
+            Err(err) => errors.push(err),
+        }
+    }
+
+    errors
+}
+
+/// Loads `path`, or the file in [`config_dir`] when there is none. A missing
+/// default file is fine, a missing `--config` file is reported.
+pub fn load(path: Option<&Path>, theories: &mut TheoryRegistry) -> (Config, Vec<ConfigError>) {
+    let default_path = config_path();
+    let file = path.unwrap_or(&default_path);
+
+    match fs::read_to_string(file) {
+        Ok(text) => parse(&text, theories),
+        Err(err) if err.kind() == io::ErrorKind::NotFound && path.is_none() => {
+            (Config::default(), Vec::new())
+        }
+        Err(err) => (
+            Config::default(),
+            vec![ConfigError::new("", err.to_string())],
+        ),
+    }
+}
+
+fn range((low, high): (f32, f32)) -> String {
+    format!("\"{low}..{high}\"")
+}
+
+/// `config` and every theory's parameters as a `config.toml`.
+pub fn to_toml(config: &Config, theories: &TheoryRegistry) -> String {
+    let mut text = String::new();
+
+    // writing to a String can't fail
+    let _ = writeln!(text, "blocks = {}", config.blocks);
+    let _ = writeln!(text, "theory = {}", Value::from(config.theory.as_str()));
+    let _ = writeln!(
+        text,
+        "color_space = \"{}\"",
+        format!("{:?}", config.color_space).to_lowercase()
+    );
+
+    for theory in theories.iter() {
+        let parameters = theory.parameters();
+
+        let _ = writeln!(text);
+        let _ = writeln!(text, "[theories.{}]", Value::from(theory.name()));
+        let _ = writeln!(text, "sat = {}", range(parameters.saturation));
+        let _ = writeln!(text, "val = {}", range(parameters.value));
+        let _ = writeln!(text, "randomness = {}", parameters.randomness);
+        let _ = writeln!(text, "jitter = {}", parameters.jitter);
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(errors: &[ConfigError]) -> Vec<String> {
+        errors.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn reads_every_key() {
+        let mut theories = TheoryRegistry::default();
+        let text = r#"
+            blocks = 8
+            theory = "split-complementary"
+            color_space = "oklch"
+
+            [theories.triad]
+            sat = [0.2, 0.4]
+            randomness = 0
+        "#;
+        let defaults = theories.get("Triad").unwrap().parameters().clone();
+        let (config, errors) = parse(text, &mut theories);
+
+        assert!(errors.is_empty(), "{:?}", messages(&errors));
+        assert_eq!(
+            config,
+            Config {
+                blocks: 8,
+                theory: String::from("SplitComplementary"),
+                color_space: ColorSpace::Oklch,
+            }
+        );
+
+        let triad = theories.get("Triad").unwrap().parameters();
+        assert_eq!(triad.saturation, (0.2, 0.4));
+        assert_eq!(triad.randomness, 0.0);
+        assert_eq!(triad.value, defaults.value);
+    }
+
+    #[test]
+    fn skips_bad_keys() {
+        let mut theories = TheoryRegistry::default();
+        let text = r#"
+            blocks = 99
+            theory = "Pastel"
+            color_space = "rgb"
+            colour = "red"
+
+            [theories]
+            nope = { jitter = 0.1 }
+            square = 3
+            triad = { sat = "0.1..0.2", jitter = 2 }
+            shades = { saturation = "0.1..0.2" }
+        "#;
+        let defaults = theories.get("Triad").unwrap().parameters().clone();
+        let (config, errors) = parse(text, &mut theories);
+
+        assert_eq!(
+            messages(&errors),
+            [
+                "blocks: expected a number from 3 to 32",
+                "color_space: expected \"hsv\" or \"oklch\"",
+                "colour: unknown key",
+                "theories.nope: unknown theory",
+                "theories.shades.saturation: unknown key",
+                "theories.square: expected a table",
+                "theories.triad.jitter: expected a number between 0 and 0.5",
+                format!(
+                    "theory: unknown theory, expected one of: {}",
+                    theories.names().join(", ")
+                )
+                .as_str(),
+            ]
+        );
+        assert_eq!(config, Config::default());
+
+        // the good sat in the broken table isn't applied either
+        assert_eq!(theories.get("Triad").unwrap().parameters(), &defaults);
+    }
+
+    #[test]
+    fn wrong_types() {
+        let mut theories = TheoryRegistry::default();
+        let (_, errors) = parse("theory = 1\ntheories = 2\n", &mut theories);
+
+        assert_eq!(
+            messages(&errors),
+            [
+                "theories: expected [theories.<name>] tables",
+                "theory: expected a theory name",
+            ]
+        );
+
+        let (config, errors) = parse("blocks = \n", &mut theories);
+        assert_eq!(config, Config::default());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].key.is_empty());
+    }
+
+    #[test]
+    fn to_toml_round_trip() {
+        let mut theories = TheoryRegistry::default();
+        theories.get_mut("Square").unwrap().parameters_mut().jitter = 0.25;
+        let config = Config {
+            blocks: 12,
+            theory: String::from("Shades"),
+            color_space: ColorSpace::Oklch,
+        };
+        let text = to_toml(&config, &theories);
+
+        let mut read_back = TheoryRegistry::default();
+        let (parsed, errors) = parse(&text, &mut read_back);
+
+        assert!(errors.is_empty(), "{:?}", messages(&errors));
+        assert_eq!(parsed, config);
+        for theory in theories.iter() {
+            assert_eq!(
+                read_back.get(theory.name()).unwrap().parameters(),
+                theory.parameters(),
+                "{}",
+                theory.name()
+            );
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! WCAG 2.1 contrast ratios and APCA lightness contrast.
+
+pub type Rgb = (u8, u8, u8);
+
+fn linearize(channel: u8) -> f32 {
+    let c = channel as f32 / 255.0;
+
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// WCAG relative luminance, 0 for black and 1 for white.
+pub fn relative_luminance((r, g, b): Rgb) -> f32 {
+    0.2126 * linearize(r) + 0.7152 * linearize(g) + 0.0722 * linearize(b)
+}
+
+/// WCAG 2.1 contrast ratio, from 1 (no contrast) to 21. Symmetric.
+pub fn wcag_ratio(a: Rgb, b: Rgb) -> f32 {
+    let la = relative_luminance(a);
+    let lb = relative_luminance(b);
+
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WcagLevel {
+    /// 7:1, enough for any text
+    Aaa,
+    /// 4.5:1, normal text at AA or large text at AAA
+    Aa,
+    /// 3:1, only large (18pt or 14pt bold) text and UI components
+    AaLarge,
+    Fail,
+}
+
+impl WcagLevel {
+    pub fn from_ratio(ratio: f32) -> Self {
+        if ratio >= 7.0 {
+            WcagLevel::Aaa
+        } else if ratio >= 4.5 {
+            WcagLevel::Aa
+        } else if ratio >= 3.0 {
+            WcagLevel::AaLarge
+        } else {
+            WcagLevel::Fail
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WcagLevel::Aaa => "AAA",
+            WcagLevel::Aa => "AA",
+            WcagLevel::AaLarge => "AA18",
+            WcagLevel::Fail => "FAIL",
+        }
+    }
+}
+
+// APCA 0.0.98G-4g constants
+const APCA_BLACK_THRESHOLD: f32 = 0.022;
+const APCA_BLACK_CLAMP: f32 = 1.414;
+const APCA_SCALE: f32 = 1.14;
+const APCA_OFFSET: f32 = 0.027;
+const APCA_LOW_CLIP: f32 = 0.1;
+const APCA_DELTA_Y_MIN: f32 = 0.0005;
+
+fn apca_luminance((r, g, b): Rgb) -> f32 {
+    let channel = |c: u8| (c as f32 / 255.0).powf(2.4);
+    let y = 0.2126729 * channel(r) + 0.7151522 * channel(g) + 0.0721750 * channel(b);
+
+    // soft clamp near black
+    if y < APCA_BLACK_THRESHOLD {
+        y + (APCA_BLACK_THRESHOLD - y).powf(APCA_BLACK_CLAMP)
+    } else {
+        y
+    }
+}
+
+/// APCA lightness contrast (Lc) of `text` on `background`, roughly -108..106.
+/// Positive for dark text on a light background, negative the other way round.
+/// Unlike the WCAG ratio the order matters.
+pub fn apca_lc(text: Rgb, background: Rgb) -> f32 {
+    let y_text = apca_luminance(text);
+    let y_bg = apca_luminance(background);
+
+    if (y_bg - y_text).abs() < APCA_DELTA_Y_MIN {
+        return 0.0;
+    }
+
+    let lc = if y_bg > y_text {
+        let sapc = (y_bg.powf(0.56) - y_text.powf(0.57)) * APCA_SCALE;
+
+        if sapc < APCA_LOW_CLIP {
+            0.0
+        } else {
+            sapc - APCA_OFFSET
+        }
+    } else {
+        let sapc = (y_bg.powf(0.65) - y_text.powf(0.62)) * APCA_SCALE;
+
+        if sapc > -APCA_LOW_CLIP {
+            0.0
+        } else {
+            sapc + APCA_OFFSET
+        }
+    };
+
+    lc * 100.0
+}
+
+/// What an APCA Lc value is good for, following the APCA bronze level guidance.
+pub fn apca_usage(lc: f32) -> &'static str {
+    let lc = lc.abs();
+
+    if lc >= 75.0 {
+        "body"
+    } else if lc >= 60.0 {
+        "text"
+    } else if lc >= 45.0 {
+        "large"
+    } else if lc >= 30.0 {
+        "non-text"
+    } else {
+        "fail"
+    }
+}
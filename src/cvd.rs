@@ -0,0 +1,111 @@
+//! Color vision deficiency simulation, using the Machado et al. (2009)
+//! matrices at full severity. Only used for display, palettes are never
+//! modified.
+
+use palette::{IntoColor, LinSrgb, Oklab, Srgb};
+
+use crate::contrast::{Rgb, relative_luminance};
+
+// applied to linear RGB
+const PROTANOPIA: [[f32; 3]; 3] = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+
+const DEUTERANOPIA: [[f32; 3]; 3] = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+];
+
+const TRITANOPIA: [[f32; 3]; 3] = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+];
+
+/// Below this OKLab distance two simulated colors are hard to tell apart.
+const CONFUSION_DISTANCE: f32 = 0.04;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum CvdMode {
+    #[default]
+    Off,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+    Achromatopsia,
+}
+
+impl CvdMode {
+    pub fn next(self) -> Self {
+        match self {
+            CvdMode::Off => CvdMode::Protanopia,
+            CvdMode::Protanopia => CvdMode::Deuteranopia,
+            CvdMode::Deuteranopia => CvdMode::Tritanopia,
+            CvdMode::Tritanopia => CvdMode::Achromatopsia,
+            CvdMode::Achromatopsia => CvdMode::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CvdMode::Off => "normal vision",
+            CvdMode::Protanopia => "protanopia",
+            CvdMode::Deuteranopia => "deuteranopia",
+            CvdMode::Tritanopia => "tritanopia",
+            CvdMode::Achromatopsia => "achromatopsia",
+        }
+    }
+
+    /// How `rgb` looks to someone with this deficiency.
+    pub fn simulate(self, rgb: Rgb) -> Rgb {
+        let matrix = match self {
+            CvdMode::Off => return rgb,
+            CvdMode::Protanopia => PROTANOPIA,
+            CvdMode::Deuteranopia => DEUTERANOPIA,
+            CvdMode::Tritanopia => TRITANOPIA,
+            CvdMode::Achromatopsia => {
+                let y = relative_luminance(rgb);
+                let gray: Srgb<f32> = LinSrgb::new(y, y, y).into_encoding();
+                return to_rgb(gray);
+            }
+        };
+
+        let linear: LinSrgb<f32> = Srgb::new(rgb.0, rgb.1, rgb.2)
+            .into_format::<f32>()
+            .into_linear();
+        let channels = [linear.red, linear.green, linear.blue];
+
+        let mixed = matrix.map(|row| {
+            row.iter()
+                .zip(channels)
+                .map(|(weight, channel)| weight * channel)
+                .sum::<f32>()
+                .clamp(0.0, 1.0)
+        });
+
+        to_rgb(LinSrgb::new(mixed[0], mixed[1], mixed[2]).into_encoding())
+    }
+
+    /// Whether `a` and `b` become too close to tell apart under this mode.
+    pub fn confuses(self, a: Rgb, b: Rgb) -> bool {
+        let to_lab = |rgb: Rgb| -> Oklab {
+            let lin: LinSrgb<f32> = Srgb::new(rgb.0, rgb.1, rgb.2)
+                .into_format::<f32>()
+                .into_linear();
+            lin.into_color()
+        };
+
+        let (a, b) = (to_lab(self.simulate(a)), to_lab(self.simulate(b)));
+        let distance = ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt();
+
+        distance < CONFUSION_DISTANCE
+    }
+}
+
+fn to_rgb(color: Srgb<f32>) -> Rgb {
+    let color: Srgb<u8> = color.into_format();
+    (color.red, color.green, color.blue)
+}
@@ -0,0 +1,396 @@
+//! Adobe Swatch Exchange (`.ase`), read by Illustrator, Photoshop and InDesign.
+//!
+//! Everything is big-endian:
+//!
+//! ```text
+//! "ASEF"  u16 major (1)  u16 minor (0)  u32 block count
+//! blocks:
+//!   u16 type    0xC001 group start, 0xC002 group end, 0x0001 color
+//!   u32 length  of the rest of the block
+//!   group start: name
+//!   color:       name, 4 byte model ("RGB ", "CMYK", "LAB ", "Gray"),
+//!                one f32 per channel, u16 type (0 global, 1 spot, 2 normal)
+//! name: u16 length in UTF-16 units including the terminating 0, UTF-16 text
+//! ```
+//!
+//! Channels are 0..1, except LAB where L is 0..1 (percent / 100) and a/b are
+//! -128..127 around a D50 white point.
+
+use palette::{
+    Lab, Srgb, Xyz,
+    chromatic_adaptation::AdaptIntoUnclamped,
+    convert::FromColorUnclamped,
+    white_point::{D50, D65},
+};
+
+use crate::widgets::content::{ColorBlock, rgb2hsv};
+
+const SIGNATURE: &[u8; 4] = b"ASEF";
+
+const BLOCK_GROUP_START: u16 = 0xC001;
+const BLOCK_GROUP_END: u16 = 0xC002;
+const BLOCK_COLOR: u16 = 0x0001;
+
+const COLOR_TYPE_NORMAL: u16 = 2;
+
+pub struct AseSwatch {
+    pub name: String,
+    /// Innermost group the swatch was in, if any.
+    pub group: Option<String>,
+    pub rgb: (u8, u8, u8),
+}
+
+pub struct AsePalette {
+    pub swatches: Vec<AseSwatch>,
+}
+
+impl AsePalette {
+    /// The name of the first group, which is how we write the palette name.
+    pub fn name(&self) -> Option<&str> {
+        self.swatches
+            .iter()
+            .find_map(|swatch| swatch.group.as_deref())
+    }
+
+    /// Groups are flattened.
+    pub fn color_blocks(&self) -> Vec<ColorBlock> {
+        self.swatches
+            .iter()
+            .map(|swatch| {
+                let (r, g, b) = swatch.rgb;
+                let (h, s, v) = rgb2hsv(r, g, b);
+                let mut block = ColorBlock::new(h, s, v);
+                block.name = swatch.name.clone();
+                block
+            })
+            .collect()
+    }
+}
+
+fn push_name(out: &mut Vec<u8>, name: &str) {
+    let units: Vec<u16> = name.encode_utf16().chain([0]).collect();
+
+    out.extend((units.len() as u16).to_be_bytes());
+    for unit in units {
+        out.extend(unit.to_be_bytes());
+    }
+}
+
+fn push_block(out: &mut Vec<u8>, block_type: u16, body: &[u8]) {
+    out.extend(block_type.to_be_bytes());
+    out.extend((body.len() as u32).to_be_bytes());
+    out.extend(body);
+}
+
+/// Writes the palette as RGB swatches inside a single group named `name`.
+pub fn encode(name: &str, blocks: &[ColorBlock]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend(SIGNATURE);
+    out.extend(1u16.to_be_bytes());
+    out.extend(0u16.to_be_bytes());
+    // group start + colors + group end
+    out.extend((blocks.len() as u32 + 2).to_be_bytes());
+
+    let mut body = Vec::new();
+    push_name(&mut body, name);
+    push_block(&mut out, BLOCK_GROUP_START, &body);
+
+    for block in blocks.iter() {
+        let label = if block.name.trim().is_empty() {
+            block.get_hex()
+        } else {
+            block.name.trim().to_string()
+        };
+        // write the same 8 bit values every other format gets
+        let (r, g, b) = block.get_rgb_values();
+
+        body.clear();
+        push_name(&mut body, &label);
+        body.extend(b"RGB ");
+        for channel in [r, g, b] {
+            body.extend((channel as f32 / 255.0).to_be_bytes());
+        }
+        body.extend(COLOR_TYPE_NORMAL.to_be_bytes());
+
+        push_block(&mut out, BLOCK_COLOR, &body);
+    }
+
+    push_block(&mut out, BLOCK_GROUP_END, &[]);
+
+    out
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize, what: &str) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("offset {}: file ends inside {what}", self.pos))?;
+
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self, what: &str) -> Result<u16, String> {
+        let bytes = self.take(2, what)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self, what: &str) -> Result<u32, String> {
+        let bytes = self.take(4, what)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self, what: &str) -> Result<f32, String> {
+        let bytes = self.take(4, what)?;
+        Ok(f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        let len = self.u16("name length")? as usize;
+        let bytes = self.take(len * 2, "name")?;
+
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .take_while(|unit| *unit != 0)
+            .collect();
+
+        String::from_utf16(&units).map_err(|_| format!("offset {start}: name is not valid UTF-16"))
+    }
+}
+
+fn to_u8(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Converts one color entry to 8 bit sRGB.
+fn read_color(reader: &mut Reader) -> Result<(u8, u8, u8), String> {
+    let start = reader.pos;
+    let model = reader.take(4, "color model")?;
+
+    match model {
+        b"RGB " => {
+            let r = reader.f32("red")?;
+            let g = reader.f32("green")?;
+            let b = reader.f32("blue")?;
+
+            Ok((to_u8(r), to_u8(g), to_u8(b)))
+        }
+        b"CMYK" => {
+            let c = reader.f32("cyan")?;
+            let m = reader.f32("magenta")?;
+            let y = reader.f32("yellow")?;
+            let k = reader.f32("black")?;
+
+            // naive conversion, there's no ICC profile to go through
+            Ok((
+                to_u8((1.0 - c) * (1.0 - k)),
+                to_u8((1.0 - m) * (1.0 - k)),
+                to_u8((1.0 - y) * (1.0 - k)),
+            ))
+        }
+        b"LAB " => {
+            let l = reader.f32("lightness")?;
+            let a = reader.f32("a")?;
+            let b = reader.f32("b")?;
+
+            let xyz = Xyz::<D50, f32>::from_color_unclamped(Lab::new(l * 100.0, a, b));
+            let xyz: Xyz<D65, f32> = xyz.adapt_into_unclamped();
+            let rgb = Srgb::from_color_unclamped(xyz);
+
+            Ok((to_u8(rgb.red), to_u8(rgb.green), to_u8(rgb.blue)))
+        }
+        b"Gray" => {
+            let gray = to_u8(reader.f32("gray")?);
+
+            Ok((gray, gray, gray))
+        }
+        _ => Err(format!(
+            "offset {start}: unknown color model \"{}\"",
+            String::from_utf8_lossy(model)
+        )),
+    }
+}
+
+pub fn decode(bytes: &[u8]) -> Result<AsePalette, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+
+    if reader.take(4, "signature")? != SIGNATURE {
+        return Err(String::from("not an Adobe Swatch Exchange file"));
+    }
+
+    let major = reader.u16("version")?;
+    let _minor = reader.u16("version")?;
+
+    if major != 1 {
+        return Err(format!("version {major} is not supported"));
+    }
+
+    let block_count = reader.u32("block count")?;
+
+    let mut palette = AsePalette {
+        swatches: Vec::new(),
+    };
+    // groups can nest, a swatch belongs to the innermost open one
+    let mut groups: Vec<String> = Vec::new();
+
+    for _ in 0..block_count {
+        let block_start = reader.pos;
+        let block_type = reader.u16("block type")?;
+        let block_len = reader.u32("block length")? as usize;
+        let body_start = reader.pos;
+
+        match block_type {
+            BLOCK_GROUP_START => groups.push(reader.name()?),
+            BLOCK_GROUP_END => {
+                groups.pop();
+            }
+            BLOCK_COLOR => {
+                let name = reader.name()?;
+                let rgb = read_color(&mut reader)?;
+                let _color_type = reader.u16("color type")?;
+
+                palette.swatches.push(AseSwatch {
+                    name,
+                    group: groups.last().cloned(),
+                    rgb,
+                });
+            }
+            _ => {
+                return Err(format!(
+                    "offset {block_start}: unknown block type {block_type:#06X}"
+                ));
+            }
+        }
+
+        // trust the length over what we parsed, some writers pad blocks
+        if reader.pos > body_start + block_len {
+            return Err(format!(
+                "offset {block_start}: block is longer than its length field"
+            ));
+        }
+        reader.pos = body_start + block_len;
+    }
+
+    if palette.swatches.is_empty() {
+        return Err(String::from("file has no colors"));
+    }
+
+    Ok(palette)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgbs(palette: &AsePalette) -> Vec<(u8, u8, u8)> {
+        palette.swatches.iter().map(|swatch| swatch.rgb).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut blocks = vec![
+            ColorBlock::new(0.0, 1.0, 1.0),
+            ColorBlock::new(212.0, 0.64, 0.71),
+            ColorBlock::new(90.0, 0.0, 0.5),
+        ];
+        blocks[1].name = String::from("primary ✓");
+
+        let palette = decode(&encode("Brand", &blocks)).unwrap();
+
+        assert_eq!(palette.name(), Some("Brand"));
+        assert_eq!(
+            rgbs(&palette),
+            blocks
+                .iter()
+                .map(|block| block.get_rgb_values())
+                .collect::<Vec<_>>()
+        );
+
+        let names: Vec<&str> = palette.swatches.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                blocks[0].get_hex().as_str(),
+                "primary ✓",
+                &blocks[2].get_hex()
+            ]
+        );
+    }
+
+    #[test]
+    fn rgb_fixture() {
+        let palette = decode(include_bytes!("../../tests/fixtures/rgb.ase")).unwrap();
+
+        assert_eq!(palette.name(), Some("Brand"));
+        assert_eq!(rgbs(&palette), [(255, 0, 0), (51, 102, 153)]);
+        assert_eq!(palette.swatches[1].name, "Steel");
+    }
+
+    #[test]
+    fn cmyk_fixture() {
+        let palette = decode(include_bytes!("../../tests/fixtures/cmyk.ase")).unwrap();
+
+        assert_eq!(rgbs(&palette), [(255, 0, 0), (128, 128, 128)]);
+    }
+
+    #[test]
+    fn lab_fixture() {
+        let palette = decode(include_bytes!("../../tests/fixtures/lab.ase")).unwrap();
+        let [white, black] = rgbs(&palette)[..] else {
+            panic!("expected two swatches");
+        };
+
+        // D50 to D65 can land a step off either end
+        assert!(
+            [white.0, white.1, white.2].iter().all(|c| *c >= 254),
+            "{white:?}"
+        );
+        assert_eq!(black, (0, 0, 0));
+    }
+
+    #[test]
+    fn gray_fixture() {
+        let palette = decode(include_bytes!("../../tests/fixtures/gray.ase")).unwrap();
+
+        assert_eq!(
+            rgbs(&palette),
+            [(0, 0, 0), (128, 128, 128), (255, 255, 255)]
+        );
+    }
+
+    #[test]
+    fn nested_groups_fixture() {
+        let palette = decode(include_bytes!("../../tests/fixtures/nested.ase")).unwrap();
+        let groups: Vec<Option<&str>> = palette
+            .swatches
+            .iter()
+            .map(|swatch| swatch.group.as_deref())
+            .collect();
+
+        assert_eq!(groups, [Some("Outer"), Some("Inner"), Some("Outer"), None]);
+        assert_eq!(palette.name(), Some("Outer"));
+        assert_eq!(palette.color_blocks().len(), 4);
+    }
+
+    #[test]
+    fn broken_fixtures() {
+        let truncated = decode(include_bytes!("../../tests/fixtures/truncated.ase"));
+        assert!(truncated.is_err_and(|err| err.contains("file ends inside")));
+
+        let bad_signature = decode(include_bytes!("../../tests/fixtures/bad_signature.ase"));
+        assert_eq!(
+            bad_signature.err().as_deref(),
+            Some("not an Adobe Swatch Exchange file")
+        );
+    }
+}
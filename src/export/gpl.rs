@@ -0,0 +1,198 @@
+//! GIMP Palette (`.gpl`), also read by Inkscape and Krita.
+//!
+//! ```text
+//! GIMP Palette
+//! Name: Brand
+//! Columns: 5
+//! #
+//!  75 158 171    primary
+//! 232  89  12    accent
+//! ```
+
+use std::fmt;
+
+use crate::widgets::content::{ColorBlock, rgb2hsv};
+
+const HEADER: &str = "GIMP Palette";
+
+#[derive(Debug)]
+pub struct GplError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for GplError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+pub struct GplPalette {
+    pub name: Option<String>,
+    pub colors: Vec<((u8, u8, u8), String)>,
+}
+
+impl GplPalette {
+    pub fn color_blocks(&self) -> Vec<ColorBlock> {
+        self.colors
+            .iter()
+            .map(|((r, g, b), name)| {
+                let (h, s, v) = rgb2hsv(*r, *g, *b);
+                let mut block = ColorBlock::new(h, s, v);
+                block.name = name.clone();
+                block
+            })
+            .collect()
+    }
+}
+
+pub fn write(name: &str, blocks: &[ColorBlock]) -> String {
+    let mut out = format!("{HEADER}\nName: {name}\nColumns: {}\n#\n", blocks.len());
+
+    for (i, block) in blocks.iter().enumerate() {
+        let (r, g, b) = block.get_rgb_values();
+        let label = if block.name.trim().is_empty() {
+            format!("color-{}", i + 1)
+        } else {
+            block.name.trim().to_string()
+        };
+
+        out.push_str(&format!("{r:>3} {g:>3} {b:>3}\t{label}\n"));
+    }
+
+    out
+}
+
+pub fn parse(text: &str) -> Result<GplPalette, GplError> {
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+    match lines.next() {
+        Some((_, first)) if first.trim() == HEADER => {}
+        _ => {
+            return Err(GplError {
+                line: 1,
+                message: format!("expected \"{HEADER}\" header"),
+            });
+        }
+    }
+
+    let mut palette = GplPalette {
+        name: None,
+        colors: Vec::new(),
+    };
+
+    for (line_no, line) in lines {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = trimmed.strip_prefix("Name:") {
+            palette.name = Some(name.trim().to_string());
+            continue;
+        }
+
+        // only used for display by the other programs
+        if trimmed.starts_with("Columns:") {
+            continue;
+        }
+
+        let mut parts = trimmed.split_whitespace();
+        let mut channels = [0u8; 3];
+
+        for (channel, label) in channels.iter_mut().zip(["red", "green", "blue"]) {
+            let part = parts.next().ok_or_else(|| GplError {
+                line: line_no,
+                message: format!("missing {label} value"),
+            })?;
+
+            *channel = part.parse().map_err(|_| GplError {
+                line: line_no,
+                message: format!("{label} value \"{part}\" is not a number from 0 to 255"),
+            })?;
+        }
+
+        let name = parts.collect::<Vec<&str>>().join(" ");
+        palette
+            .colors
+            .push(((channels[0], channels[1], channels[2]), name));
+    }
+
+    if palette.colors.is_empty() {
+        return Err(GplError {
+            line: text.lines().count().max(1),
+            message: String::from("palette has no colors"),
+        });
+    }
+
+    Ok(palette)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut blocks = vec![
+            ColorBlock::new(0.0, 1.0, 1.0),
+            ColorBlock::new(212.0, 0.64, 0.71),
+            ColorBlock::new(90.0, 0.0, 0.5),
+        ];
+        blocks[1].name = String::from("primary blue");
+
+        let palette = parse(&write("Brand", &blocks)).unwrap();
+
+        assert_eq!(palette.name.as_deref(), Some("Brand"));
+        assert_eq!(palette.colors[1].1, "primary blue");
+
+        let hexes: Vec<String> = palette.color_blocks().iter().map(|b| b.get_hex()).collect();
+        let expected: Vec<String> = blocks.iter().map(|b| b.get_hex()).collect();
+        assert_eq!(hexes, expected);
+    }
+
+    #[test]
+    fn reads_gimp_files() {
+        let text = "GIMP Palette\nName: Brand\nColumns: 5\n#\n# a comment\n\n 75 158 171\tprimary  blue\n232  89  12\n";
+        let palette = parse(text).unwrap();
+
+        assert_eq!(palette.name.as_deref(), Some("Brand"));
+        assert_eq!(
+            palette.colors,
+            [
+                ((75, 158, 171), String::from("primary blue")),
+                ((232, 89, 12), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("", 1, "expected \"GIMP Palette\" header"),
+            ("JASC-PAL\n0 0 0\n", 1, "expected \"GIMP Palette\" header"),
+            ("GIMP Palette\n1 2 3\n4 5\n", 3, "missing blue value"),
+            (
+                "GIMP Palette\n256 0 0\n",
+                2,
+                "red value \"256\" is not a number from 0 to 255",
+            ),
+            (
+                "GIMP Palette\n0 x 0\n",
+                2,
+                "green value \"x\" is not a number from 0 to 255",
+            ),
+            ("GIMP Palette\nName: empty\n#\n", 3, "palette has no colors"),
+        ];
+
+        for (text, line, message) in cases {
+            let err = parse(text).err().unwrap();
+            assert_eq!(
+                (err.line, err.message.as_str()),
+                (line, message),
+                "{text:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Writers that turn a palette into files other tools understand.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use strum_macros::EnumIter;
+
+use crate::{
+    color_palette::{MAX_BLOCKS, MIN_BLOCKS},
+    terminal_theme::TerminalTheme,
+    widgets::content::ColorBlock,
+};
+
+/// Files [`import`] knows how to read.
+pub const IMPORT_EXTENSIONS: &[&str] = &["gpl", "ase"];
+
+pub mod ase;
+pub mod gpl;
+pub mod terminal;
+pub mod web;
+
+#[derive(Copy, Clone, Debug, PartialEq, EnumIter, ValueEnum)]
+pub enum ExportFormat {
+    /// CSS custom properties in a `:root` block
+    Css,
+    /// SCSS variables
+    Scss,
+    /// Tailwind config snippet with the colors in `theme.extend.colors`,
+    /// to merge into `tailwind.config.js`
+    TailwindJs,
+    /// The same snippet for `tailwind.config.ts`
+    TailwindTs,
+    /// GIMP palette, also used by Inkscape and Krita
+    Gpl,
+    /// Adobe Swatch Exchange, binary
+    Ase,
+    /// Alacritty terminal colors (TOML)
+    Alacritty,
+    /// Kitty terminal theme (conf)
+    Kitty,
+    /// WezTerm color scheme (TOML)
+    Wezterm,
+    /// foot terminal colors (ini)
+    Foot,
+    /// Windows Terminal color scheme (JSON)
+    WindowsTerminal,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Css => "css",
+            ExportFormat::Scss => "scss",
+            ExportFormat::TailwindJs => "js",
+            ExportFormat::TailwindTs => "ts",
+            ExportFormat::Gpl => "gpl",
+            ExportFormat::Ase => "ase",
+            ExportFormat::Alacritty | ExportFormat::Wezterm => "toml",
+            ExportFormat::Kitty => "conf",
+            ExportFormat::Foot => "ini",
+            ExportFormat::WindowsTerminal => "json",
+        }
+    }
+
+    pub fn is_binary(&self) -> bool {
+        *self == ExportFormat::Ase
+    }
+
+    /// Formats that export a [`TerminalTheme`] derived from the palette
+    /// rather than the palette colors themselves.
+    pub fn is_terminal_theme(&self) -> bool {
+        matches!(
+            self,
+            ExportFormat::Alacritty
+                | ExportFormat::Kitty
+                | ExportFormat::Wezterm
+                | ExportFormat::Foot
+                | ExportFormat::WindowsTerminal
+        )
+    }
+
+    /// File name used when the palette itself doesn't have one.
+    pub fn default_file_name(&self) -> &'static str {
+        match self {
+            ExportFormat::Css => "palette.css",
+            ExportFormat::Scss => "_palette.scss",
+            ExportFormat::TailwindJs => "palette.tailwind.js",
+            ExportFormat::TailwindTs => "palette.tailwind.ts",
+            ExportFormat::Gpl => "palette.gpl",
+            ExportFormat::Ase => "palette.ase",
+            ExportFormat::Alacritty => "alacritty-colors.toml",
+            ExportFormat::Kitty => "kitty-theme.conf",
+            ExportFormat::Wezterm => "wezterm-colors.toml",
+            ExportFormat::Foot => "foot-colors.ini",
+            ExportFormat::WindowsTerminal => "windows-terminal-scheme.json",
+        }
+    }
+
+    pub fn output_path(&self, palette_name: &str) -> PathBuf {
+        let palette_name = palette_name.trim();
+
+        match self {
+            _ if palette_name.is_empty() => PathBuf::from(self.default_file_name()),
+            ExportFormat::Scss => PathBuf::from(format!("_{palette_name}.scss")),
+            // both are .toml, keep them from overwriting each other
+            ExportFormat::Alacritty => PathBuf::from(format!("{palette_name}-alacritty.toml")),
+            ExportFormat::Wezterm => PathBuf::from(format!("{palette_name}-wezterm.toml")),
+            // never `tailwind.config.*`, that would replace the project's own config
+            ExportFormat::TailwindJs | ExportFormat::TailwindTs => {
+                PathBuf::from(format!("{palette_name}.tailwind.{}", self.extension()))
+            }
+            _ => PathBuf::from(format!("{palette_name}.{}", self.extension())),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ExportFormat::Css => "CSS",
+            ExportFormat::Scss => "SCSS",
+            ExportFormat::TailwindJs => "Tailwind (JS)",
+            ExportFormat::TailwindTs => "Tailwind (TS)",
+            ExportFormat::Gpl => "GIMP Palette",
+            ExportFormat::Ase => "Adobe Swatch Exchange",
+            ExportFormat::Alacritty => "Alacritty",
+            ExportFormat::Kitty => "Kitty",
+            ExportFormat::Wezterm => "WezTerm",
+            ExportFormat::Foot => "foot",
+            ExportFormat::WindowsTerminal => "Windows Terminal",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+pub fn export(format: ExportFormat, name: &str, color_blocks: &[ColorBlock]) -> Vec<u8> {
+    let colors = named_colors(color_blocks);
+    let theme = || TerminalTheme::from_blocks(color_blocks);
+    let name = match name.trim() {
+        "" => "Palette",
+        name => name,
+    };
+
+    match format {
+        ExportFormat::Css => web::css(&colors).into_bytes(),
+        ExportFormat::Scss => web::scss(&colors).into_bytes(),
+        ExportFormat::TailwindJs => web::tailwind_js(&colors).into_bytes(),
+        ExportFormat::TailwindTs => web::tailwind_ts(&colors).into_bytes(),
+        ExportFormat::Gpl => gpl::write(name, color_blocks).into_bytes(),
+        ExportFormat::Ase => ase::encode(name, color_blocks),
+        ExportFormat::Alacritty => terminal::alacritty(&theme()).into_bytes(),
+        ExportFormat::Kitty => terminal::kitty(&theme()).into_bytes(),
+        ExportFormat::Wezterm => terminal::wezterm(name, &theme()).into_bytes(),
+        ExportFormat::Foot => terminal::foot(&theme()).into_bytes(),
+        ExportFormat::WindowsTerminal => terminal::windows_terminal(name, &theme()).into_bytes(),
+    }
+}
+
+/// What the export popup shows, binary formats just get a summary.
+pub fn preview(format: ExportFormat, name: &str, color_blocks: &[ColorBlock]) -> String {
+    let bytes = export(format, name, color_blocks);
+
+    if format.is_binary() {
+        format!("{} colors, {} bytes", color_blocks.len(), bytes.len())
+    } else {
+        String::from_utf8_lossy(&bytes).to_string()
+    }
+}
+
+/// A palette read from another program's file.
+pub struct Imported {
+    pub name: String,
+    pub color_blocks: Vec<ColorBlock>,
+    /// Colors past [`MAX_BLOCKS`] that didn't fit.
+    pub dropped: usize,
+}
+
+/// Reads a palette written by another program, picked by file extension.
+/// Palettes with fewer than [`MIN_BLOCKS`] colors are rejected, anything past
+/// [`MAX_BLOCKS`] is cut off and counted in [`Imported::dropped`].
+pub fn import(path: &Path) -> Result<Imported, String> {
+    let file_stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let (name, mut color_blocks) = match extension.as_str() {
+        "gpl" => {
+            let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+            let palette = gpl::parse(&text).map_err(|err| err.to_string())?;
+
+            (
+                palette.name.clone().unwrap_or(file_stem),
+                palette.color_blocks(),
+            )
+        }
+        "ase" => {
+            let bytes = fs::read(path).map_err(|err| err.to_string())?;
+            let palette = ase::decode(&bytes)?;
+
+            (
+                palette.name().map(String::from).unwrap_or(file_stem),
+                palette.color_blocks(),
+            )
+        }
+        _ => return Err(format!("can't import .{extension} files")),
+    };
+
+    if color_blocks.len() < MIN_BLOCKS {
+        return Err(format!(
+            "{} colors, a palette needs at least {MIN_BLOCKS}",
+            color_blocks.len()
+        ));
+    }
+
+    let dropped = color_blocks.len().saturating_sub(MAX_BLOCKS);
+    color_blocks.truncate(MAX_BLOCKS);
+
+    Ok(Imported {
+        name,
+        color_blocks,
+        dropped,
+    })
+}
+
+/// Block names turned into identifiers, with `color-N` for unnamed blocks.
+/// Duplicates get a numbered suffix so no variable overwrites another.
+pub fn named_colors(color_blocks: &[ColorBlock]) -> Vec<(String, String)> {
+    let mut colors: Vec<(String, String)> = Vec::new();
+
+    for (i, block) in color_blocks.iter().enumerate() {
+        let mut name = slugify(&block.name);
+
+        if name.is_empty() {
+            name = format!("color-{}", i + 1);
+        }
+
+        let base = name.clone();
+        let mut suffix = 2;
+
+        while colors.iter().any(|(taken, _)| *taken == name) {
+            name = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+
+        colors.push((name, block.get_hex()));
+    }
+
+    colors
+}
+
+/// Lowercase, ASCII letters and digits only, everything else becomes `-`.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+
+    // CSS and SCSS identifiers can't start with a digit
+    if slug.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("color-{slug}")
+    } else {
+        slug.to_string()
+    }
+}
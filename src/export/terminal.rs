@@ -0,0 +1,159 @@
+//! Color scheme files for terminal emulators.
+
+use crate::terminal_theme::{ANSI_NAMES, TerminalTheme, hex};
+
+pub fn alacritty(theme: &TerminalTheme) -> String {
+    let mut out = format!(
+        "[colors.primary]\nbackground = \"{}\"\nforeground = \"{}\"\n\n",
+        hex(theme.background),
+        hex(theme.foreground)
+    );
+
+    out.push_str(&format!(
+        "[colors.cursor]\ntext = \"{}\"\ncursor = \"{}\"\n\n",
+        hex(theme.background),
+        hex(theme.cursor)
+    ));
+
+    out.push_str(&format!(
+        "[colors.selection]\ntext = \"{}\"\nbackground = \"{}\"\n",
+        hex(theme.foreground),
+        hex(theme.selection_background)
+    ));
+
+    for (section, offset) in [("normal", 0), ("bright", 8)] {
+        out.push_str(&format!("\n[colors.{section}]\n"));
+
+        for (i, name) in ANSI_NAMES.iter().enumerate() {
+            out.push_str(&format!("{name} = \"{}\"\n", hex(theme.ansi[offset + i])));
+        }
+    }
+
+    out
+}
+
+pub fn kitty(theme: &TerminalTheme) -> String {
+    let mut out = format!(
+        "background {}\nforeground {}\ncursor {}\ncursor_text_color {}\nselection_background {}\nselection_foreground {}\n\n",
+        hex(theme.background),
+        hex(theme.foreground),
+        hex(theme.cursor),
+        hex(theme.background),
+        hex(theme.selection_background),
+        hex(theme.foreground),
+    );
+
+    for (i, color) in theme.ansi.iter().enumerate() {
+        out.push_str(&format!("color{i} {}\n", hex(*color)));
+    }
+
+    out
+}
+
+fn quoted_list(colors: &[(u8, u8, u8)]) -> String {
+    colors
+        .iter()
+        .map(|color| format!("\"{}\"", hex(*color)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn wezterm(name: &str, theme: &TerminalTheme) -> String {
+    format!(
+        "[colors]\nforeground = \"{}\"\nbackground = \"{}\"\ncursor_bg = \"{}\"\ncursor_fg = \"{}\"\ncursor_border = \"{}\"\nselection_bg = \"{}\"\nselection_fg = \"{}\"\nansi = [{}]\nbrights = [{}]\n\n[metadata]\nname = {}\n",
+        hex(theme.foreground),
+        hex(theme.background),
+        hex(theme.cursor),
+        hex(theme.background),
+        hex(theme.cursor),
+        hex(theme.selection_background),
+        hex(theme.foreground),
+        quoted_list(&theme.ansi[..8]),
+        quoted_list(&theme.ansi[8..]),
+        toml::Value::from(name),
+    )
+}
+
+pub fn foot(theme: &TerminalTheme) -> String {
+    // foot wants bare rrggbb without the '#'
+    let bare = |color| hex(color)[1..].to_string();
+
+    let mut out = format!(
+        "[cursor]\ncolor={} {}\n\n[colors]\nforeground={}\nbackground={}\nselection-foreground={}\nselection-background={}\n",
+        bare(theme.background),
+        bare(theme.cursor),
+        bare(theme.foreground),
+        bare(theme.background),
+        bare(theme.foreground),
+        bare(theme.selection_background),
+    );
+
+    for (i, color) in theme.ansi.iter().enumerate() {
+        let (kind, idx) = if i < 8 {
+            ("regular", i)
+        } else {
+            ("bright", i - 8)
+        };
+        out.push_str(&format!("{kind}{idx}={}\n", bare(*color)));
+    }
+
+    out
+}
+
+/// `text` as a quoted JSON string.
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+pub fn windows_terminal(name: &str, theme: &TerminalTheme) -> String {
+    // windows terminal calls magenta purple
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "purple", "cyan", "white",
+    ];
+
+    let mut fields = vec![
+        (String::from("name"), name.to_string()),
+        (String::from("background"), hex(theme.background)),
+        (String::from("foreground"), hex(theme.foreground)),
+        (String::from("cursorColor"), hex(theme.cursor)),
+        (
+            String::from("selectionBackground"),
+            hex(theme.selection_background),
+        ),
+    ];
+
+    for (i, color_name) in NAMES.iter().enumerate() {
+        fields.push((color_name.to_string(), hex(theme.ansi[i])));
+    }
+
+    for (i, color_name) in NAMES.iter().enumerate() {
+        let mut bright = String::from("bright");
+        bright.push_str(&color_name[..1].to_uppercase());
+        bright.push_str(&color_name[1..]);
+
+        fields.push((bright, hex(theme.ansi[i + 8])));
+    }
+
+    let body = fields
+        .iter()
+        .map(|(key, value)| format!("  \"{key}\": {}", json_string(value)))
+        .collect::<Vec<String>>()
+        .join(",\n");
+
+    format!("{{\n{body}\n}}\n")
+}
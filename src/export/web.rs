@@ -0,0 +1,48 @@
+//! CSS, SCSS and Tailwind config output. Every function takes
+//! `(identifier, #RRGGBB)` pairs from [`super::named_colors`].
+
+pub fn css(colors: &[(String, String)]) -> String {
+    let mut out = String::from(":root {\n");
+
+    for (name, hex) in colors {
+        out.push_str(&format!("  --{name}: {hex};\n"));
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+pub fn scss(colors: &[(String, String)]) -> String {
+    let mut out = String::new();
+
+    for (name, hex) in colors {
+        out.push_str(&format!("${name}: {hex};\n"));
+    }
+
+    out
+}
+
+fn tailwind_theme(colors: &[(String, String)]) -> String {
+    let mut out = String::from("  theme: {\n    extend: {\n      colors: {\n");
+
+    for (name, hex) in colors {
+        out.push_str(&format!("        '{name}': '{hex}',\n"));
+    }
+
+    out.push_str("      },\n    },\n  },\n");
+    out
+}
+
+pub fn tailwind_js(colors: &[(String, String)]) -> String {
+    format!(
+        "/** @type {{import('tailwindcss').Config}} */\nmodule.exports = {{\n{}}};\n",
+        tailwind_theme(colors)
+    )
+}
+
+pub fn tailwind_ts(colors: &[(String, String)]) -> String {
+    format!(
+        "import type {{ Config }} from 'tailwindcss';\n\nexport default {{\n{}}} satisfies Config;\n",
+        tailwind_theme(colors)
+    )
+}
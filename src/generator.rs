@@ -0,0 +1,114 @@
+use clap::ValueEnum;
+use palette::Hsv;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    theory::{Base, ColorTheory},
+    widgets::content::ColorBlock,
+};
+
+/// ChaCha8 produces the same stream for a seed on every platform and rand
+/// release, which `StdRng` doesn't promise.
+pub type PaletteRng = ChaCha8Rng;
+
+pub fn random_seed() -> u64 {
+    rand::random()
+}
+
+/// Color space the theories rotate hues in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSpace {
+    /// Rotate HSV hue, saturation and value are picked at random
+    #[default]
+    Hsv,
+    /// Rotate OKLCH hue with the same lightness and chroma for every block
+    Oklch,
+}
+
+impl ColorSpace {
+    pub fn toggle(&self) -> Self {
+        match self {
+            ColorSpace::Hsv => ColorSpace::Oklch,
+            ColorSpace::Oklch => ColorSpace::Hsv,
+        }
+    }
+}
+
+/// Fills every unlocked block using `theory`.
+///
+/// The result only depends on the seed, the theory, the block order and the
+/// locked blocks, so replaying a seed gives the same palette.
+pub fn generate(theory: &dyn ColorTheory, space: ColorSpace, blocks: &mut [ColorBlock], seed: u64) {
+    let mut rng = PaletteRng::seed_from_u64(seed);
+
+    let locks: Vec<Option<Hsv>> = blocks
+        .iter()
+        .map(|block| block.locked.then_some(block.hsv))
+        .collect();
+
+    let base = Base::new(space, &locks, &mut rng);
+    let colors = theory.generate(&base, &locks, blocks.len(), &mut rng);
+
+    for (block, color) in blocks.iter_mut().zip(colors) {
+        if !block.locked {
+            block.hsv = color;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color_palette::ColorPalette, theory::TheoryRegistry};
+
+    fn palette_with_lock() -> ColorPalette {
+        let mut palette = ColorPalette::new(6);
+        palette[2] = ColorBlock::new(200.0, 0.6, 0.7);
+        palette[2].locked = true;
+        palette
+    }
+
+    fn hsv_values(palette: &ColorPalette) -> Vec<(f32, f32, f32)> {
+        palette.iter().map(|block| block.get_hsv_values()).collect()
+    }
+
+    #[test]
+    fn same_seed_same_colors() {
+        let theories = TheoryRegistry::default();
+
+        for theory in theories.iter() {
+            for space in [ColorSpace::Hsv, ColorSpace::Oklch] {
+                let mut first = palette_with_lock();
+                let mut second = palette_with_lock();
+
+                generate(theory, space, &mut first, 42);
+                generate(theory, space, &mut second, 42);
+
+                assert_eq!(
+                    hsv_values(&first),
+                    hsv_values(&second),
+                    "{} in {space:?}",
+                    theory.name()
+                );
+                assert_eq!(first[2].get_hsv_values(), (200.0, 0.6, 0.7));
+            }
+        }
+    }
+
+    #[test]
+    fn other_seed_other_colors() {
+        let theories = TheoryRegistry::default();
+        let theory = theories.get("analogous").unwrap();
+
+        let mut first = palette_with_lock();
+        let mut second = palette_with_lock();
+
+        generate(theory, ColorSpace::Hsv, &mut first, 1);
+        generate(theory, ColorSpace::Hsv, &mut second, 2);
+
+        assert_ne!(hsv_values(&first), hsv_values(&second));
+    }
+}
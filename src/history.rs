@@ -0,0 +1,77 @@
+use std::collections::VecDeque;
+
+use crate::color_palette::ColorPalette;
+
+pub const HISTORY_LIMIT: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    /// What was done to the palette right after this snapshot was taken.
+    pub label: &'static str,
+
+    pub palette: ColorPalette,
+    pub seed: Option<u64>,
+}
+
+/// Undo/redo stacks of whole palettes. Snapshots are tiny (a few dozen blocks at
+/// most), so storing full copies is simpler than recording individual edits.
+#[derive(Default)]
+pub struct History {
+    undo_stack: VecDeque<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+}
+
+impl History {
+    /// Call with the state from *before* a change.
+    pub fn record(&mut self, snapshot: Snapshot) {
+        if self.undo_stack.len() == HISTORY_LIMIT {
+            self.undo_stack.pop_front();
+        }
+
+        self.undo_stack.push_back(snapshot);
+        self.redo_stack.clear();
+    }
+
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo_stack.pop_back()?;
+
+        // the current state is what the undone action produced
+        let current = Snapshot {
+            label: previous.label,
+            ..current
+        };
+        self.redo_stack.push(current);
+
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo_stack.pop()?;
+
+        self.undo_stack.push_back(Snapshot {
+            label: next.label,
+            ..current
+        });
+
+        Some(next)
+    }
+
+    /// Undoes everything up to and including the `steps_back`-th entry of
+    /// [`History::entries`]. The skipped states can still be redone.
+    pub fn jump(&mut self, steps_back: usize, mut current: Snapshot) -> Option<Snapshot> {
+        if steps_back >= self.undo_stack.len() {
+            return None;
+        }
+
+        for _ in 0..=steps_back {
+            current = self.undo(current)?;
+        }
+
+        Some(current)
+    }
+
+    /// Previous palettes, most recent first.
+    pub fn entries(&self) -> impl Iterator<Item = &Snapshot> {
+        self.undo_stack.iter().rev()
+    }
+}
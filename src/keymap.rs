@@ -0,0 +1,1060 @@
+//! Key bindings, the defaults below with `keymap.toml` on top:
+//!
+//! ```toml
+//! [main]
+//! select-previous = ["h", "left"]
+//! select-next = ["l", "right"]
+//! toggle-lock = "k"
+//! history = "H"
+//! undo = []                   # unbound
+//!
+//! [sliders]
+//! decrease = "h"
+//! increase = "l"
+//! ```
+//!
+//! Each table is a page, each key an action. An action listed in the file
+//! loses its default keys. Keys are a character or a name like `enter`,
+//! `space` or `pageup`, with `ctrl+`, `alt+` or `shift+` in front.
+//!
+//! A key bound to two actions on the same page is a conflict, and then the
+//! whole file is ignored so nothing ends up half rebound.
+
+use std::{fmt, fs, io, path::Path, path::PathBuf, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use toml::Value;
+
+use crate::config::{self, ConfigError};
+
+pub const KEYMAP_FILE_NAME: &str = "keymap.toml";
+
+/// Where a binding applies, one per page. The open and import pickers
+/// share one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Context {
+    Main,
+    Theories,
+    EditColor,
+    Save,
+    Files,
+    Library,
+    Seed,
+    History,
+    Export,
+    TerminalTheme,
+    Sliders,
+}
+
+const CONTEXTS: [(Context, &str, &str); 11] = [
+    (Context::Main, "main", "Main"),
+    (Context::Theories, "theories", "Select Theory"),
+    (Context::EditColor, "edit-color", "Edit Color"),
+    (Context::Save, "save", "Save Palette"),
+    (Context::Files, "files", "Open Palette"),
+    (Context::Library, "library", "Library"),
+    (Context::Seed, "seed", "Enter Seed"),
+    (Context::History, "history", "History"),
+    (Context::Export, "export", "Export"),
+    (Context::TerminalTheme, "terminal-theme", "Terminal Theme"),
+    (Context::Sliders, "sliders", "Adjust Color"),
+];
+
+impl Context {
+    /// The table name in `keymap.toml`.
+    pub fn name(self) -> &'static str {
+        CONTEXTS.iter().find(|(c, _, _)| *c == self).unwrap().1
+    }
+
+    pub fn title(self) -> &'static str {
+        CONTEXTS.iter().find(|(c, _, _)| *c == self).unwrap().2
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        CONTEXTS
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .map(|(c, _, _)| *c)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    SelectPrevious,
+    SelectNext,
+    /// Adds a block right after the selected one.
+    AddBlock,
+    AddBlockBefore,
+    DuplicateBlock,
+    DeleteBlock,
+    MoveBlockLeft,
+    MoveBlockRight,
+    /// Marks the selected block, or swaps it with the marked one.
+    SwapBlock,
+    Theories,
+    EditColor,
+    ToggleLock,
+    /// Toggles the lock on block 1-9 without selecting it.
+    LockBlock(u8),
+    CopyHex,
+    Save,
+    Open,
+    Import,
+    Library,
+    Export,
+    TerminalTheme,
+    Sliders,
+    ToggleColorSpace,
+    ToggleContrast,
+    CycleCvd,
+    Generate,
+    Undo,
+    Redo,
+    History,
+    EnterSeed,
+
+    // popups
+    Close,
+    Confirm,
+    Up,
+    Down,
+    First,
+    Last,
+    Parent,
+    SwitchField,
+    Clear,
+    Copy,
+    PreviousFormat,
+    NextFormat,
+    NextMode,
+    Decrease,
+    Increase,
+    DecreaseCoarse,
+    IncreaseCoarse,
+}
+
+const ACTIONS: [(Action, &str); 47] = [
+    (Action::Quit, "quit"),
+    (Action::Help, "help"),
+    (Action::SelectPrevious, "select-previous"),
+    (Action::SelectNext, "select-next"),
+    (Action::AddBlock, "add-block"),
+    (Action::AddBlockBefore, "add-block-before"),
+    (Action::DuplicateBlock, "duplicate-block"),
+    (Action::DeleteBlock, "delete-block"),
+    (Action::MoveBlockLeft, "move-block-left"),
+    (Action::MoveBlockRight, "move-block-right"),
+    (Action::SwapBlock, "swap-block"),
+    (Action::Theories, "theories"),
+    (Action::EditColor, "edit-color"),
+    (Action::ToggleLock, "toggle-lock"),
+    (Action::CopyHex, "copy-hex"),
+    (Action::Save, "save"),
+    (Action::Open, "open"),
+    (Action::Import, "import"),
+    (Action::Library, "library"),
+    (Action::Export, "export"),
+    (Action::TerminalTheme, "terminal-theme"),
+    (Action::Sliders, "sliders"),
+    (Action::ToggleColorSpace, "toggle-color-space"),
+    (Action::ToggleContrast, "toggle-contrast"),
+    (Action::CycleCvd, "cycle-cvd"),
+    (Action::Generate, "generate"),
+    (Action::Undo, "undo"),
+    (Action::Redo, "redo"),
+    (Action::History, "history"),
+    (Action::EnterSeed, "enter-seed"),
+    (Action::Close, "close"),
+    (Action::Confirm, "confirm"),
+    (Action::Up, "up"),
+    (Action::Down, "down"),
+    (Action::First, "first"),
+    (Action::Last, "last"),
+    (Action::Parent, "parent"),
+    (Action::SwitchField, "switch-field"),
+    (Action::Clear, "clear"),
+    (Action::Copy, "copy"),
+    (Action::PreviousFormat, "previous-format"),
+    (Action::NextFormat, "next-format"),
+    (Action::NextMode, "next-mode"),
+    (Action::Decrease, "decrease"),
+    (Action::Increase, "increase"),
+    (Action::DecreaseCoarse, "decrease-coarse"),
+    (Action::IncreaseCoarse, "increase-coarse"),
+];
+
+impl Action {
+    /// The key in `keymap.toml`.
+    pub fn name(self) -> String {
+        match self {
+            Action::LockBlock(n) => format!("lock-block-{n}"),
+            action => ACTIONS
+                .iter()
+                .find(|(a, _)| *a == action)
+                .unwrap()
+                .1
+                .to_string(),
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        if let Some(n) = name.strip_prefix("lock-block-") {
+            return n
+                .parse::<u8>()
+                .ok()
+                .filter(|n| (1..=9).contains(n))
+                .map(Action::LockBlock);
+        }
+
+        ACTIONS
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(action, _)| *action)
+    }
+}
+
+/// A key with its modifiers. Shift is folded into characters, so `A` and
+/// `shift+a` are the same binding.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        *self == KeyBinding::new(key_event.code, key_event.modifiers)
+    }
+}
+
+const KEY_NAMES: [(KeyCode, &str); 16] = [
+    (KeyCode::Char(' '), "space"),
+    (KeyCode::Enter, "enter"),
+    (KeyCode::Esc, "esc"),
+    (KeyCode::Tab, "tab"),
+    (KeyCode::BackTab, "backtab"),
+    (KeyCode::Backspace, "backspace"),
+    (KeyCode::Delete, "delete"),
+    (KeyCode::Insert, "insert"),
+    (KeyCode::Left, "left"),
+    (KeyCode::Right, "right"),
+    (KeyCode::Up, "up"),
+    (KeyCode::Down, "down"),
+    (KeyCode::Home, "home"),
+    (KeyCode::End, "end"),
+    (KeyCode::PageUp, "pageup"),
+    (KeyCode::PageDown, "pagedown"),
+];
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+
+    let name = name.to_lowercase();
+
+    match name.as_str() {
+        "escape" => return Some(KeyCode::Esc),
+        "del" => return Some(KeyCode::Delete),
+        _ => {}
+    }
+
+    if let Some(n) = name.strip_prefix('f')
+        && let Ok(n) = n.parse::<u8>()
+        && (1..=12).contains(&n)
+    {
+        return Some(KeyCode::F(n));
+    }
+
+    KEY_NAMES
+        .iter()
+        .find(|(_, n)| *n == name)
+        .map(|(code, _)| *code)
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+
+        // the key itself can be a '+'
+        let (prefix, key) = if text == "+" {
+            ("", "+")
+        } else if let Some(prefix) = text.strip_suffix("++") {
+            (prefix, "+")
+        } else {
+            text.rsplit_once('+').unwrap_or(("", text))
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier \"{modifier}\" in \"{text}\"")),
+            };
+        }
+
+        let mut code = key_code(key).ok_or_else(|| format!("unknown key \"{key}\""))?;
+
+        if modifiers.contains(KeyModifiers::SHIFT) {
+            code = match code {
+                KeyCode::Char(c) => KeyCode::Char(c.to_ascii_uppercase()),
+                KeyCode::Tab => KeyCode::BackTab,
+                code => code,
+            };
+        }
+
+        Ok(KeyBinding::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            code => match KEY_NAMES.iter().find(|(c, _)| *c == code) {
+                Some((_, name)) => write!(f, "{name}"),
+                None => write!(f, "{code:?}"),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Binding {
+    pub context: Context,
+    pub action: Action,
+    pub keys: Vec<KeyBinding>,
+    pub description: &'static str,
+}
+
+// (context, action, default keys, what it does)
+const DEFAULTS: &[(Context, Action, &[&str], &str)] = &[
+    (
+        Context::Main,
+        Action::SelectPrevious,
+        &["left"],
+        "select previous block",
+    ),
+    (
+        Context::Main,
+        Action::SelectNext,
+        &["right"],
+        "select next block",
+    ),
+    (Context::Main, Action::Generate, &["space"], "generate"),
+    (
+        Context::Main,
+        Action::ToggleLock,
+        &["l"],
+        "lock selected block",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(1),
+        &["alt+1"],
+        "lock block 1",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(2),
+        &["alt+2"],
+        "lock block 2",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(3),
+        &["alt+3"],
+        "lock block 3",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(4),
+        &["alt+4"],
+        "lock block 4",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(5),
+        &["alt+5"],
+        "lock block 5",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(6),
+        &["alt+6"],
+        "lock block 6",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(7),
+        &["alt+7"],
+        "lock block 7",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(8),
+        &["alt+8"],
+        "lock block 8",
+    ),
+    (
+        Context::Main,
+        Action::LockBlock(9),
+        &["alt+9"],
+        "lock block 9",
+    ),
+    (
+        Context::Main,
+        Action::MoveBlockLeft,
+        &["shift+left"],
+        "move block left",
+    ),
+    (
+        Context::Main,
+        Action::MoveBlockRight,
+        &["shift+right"],
+        "move block right",
+    ),
+    (Context::Main, Action::AddBlock, &["a"], "add block after"),
+    (
+        Context::Main,
+        Action::AddBlockBefore,
+        &["A"],
+        "add block before",
+    ),
+    (
+        Context::Main,
+        Action::DuplicateBlock,
+        &["y"],
+        "duplicate block",
+    ),
+    (
+        Context::Main,
+        Action::SwapBlock,
+        &["p"],
+        "mark / swap block",
+    ),
+    (Context::Main, Action::DeleteBlock, &["d"], "delete block"),
+    (Context::Main, Action::Theories, &["x"], "select theory"),
+    (Context::Main, Action::EditColor, &["z"], "edit color"),
+    (Context::Main, Action::Sliders, &["n"], "adjust color"),
+    (Context::Main, Action::CopyHex, &["c"], "copy hex"),
+    (Context::Main, Action::Undo, &["u"], "undo"),
+    (Context::Main, Action::Redo, &["ctrl+r"], "redo"),
+    (Context::Main, Action::History, &["h"], "history"),
+    (Context::Main, Action::EnterSeed, &["r"], "enter seed"),
+    (Context::Main, Action::Save, &["s"], "save palette"),
+    (Context::Main, Action::Open, &["o"], "open palette"),
+    (Context::Main, Action::Import, &["i"], "import palette"),
+    (Context::Main, Action::Library, &["b"], "library"),
+    (Context::Main, Action::Export, &["e"], "export"),
+    (
+        Context::Main,
+        Action::TerminalTheme,
+        &["t"],
+        "terminal theme",
+    ),
+    (
+        Context::Main,
+        Action::ToggleColorSpace,
+        &["m"],
+        "HSV / OKLCH",
+    ),
+    (
+        Context::Main,
+        Action::ToggleContrast,
+        &["w"],
+        "contrast matrix",
+    ),
+    (
+        Context::Main,
+        Action::CycleCvd,
+        &["v"],
+        "color blindness preview",
+    ),
+    (Context::Main, Action::Help, &["?", "f1"], "keys"),
+    (Context::Main, Action::Quit, &["q"], "quit"),
+    (Context::Theories, Action::Up, &["up"], "previous theory"),
+    (Context::Theories, Action::Down, &["down"], "next theory"),
+    (Context::Theories, Action::First, &["left"], "first theory"),
+    (Context::Theories, Action::Last, &["right"], "last theory"),
+    (
+        Context::Theories,
+        Action::Confirm,
+        &["enter", "space"],
+        "use theory",
+    ),
+    (
+        Context::Theories,
+        Action::Close,
+        &["esc", "x", "q"],
+        "close",
+    ),
+    (Context::EditColor, Action::Confirm, &["enter"], "apply"),
+    (
+        Context::EditColor,
+        Action::Clear,
+        // terminals send ctrl+backspace as ctrl+h or ctrl+w
+        &["ctrl+backspace", "ctrl+h", "ctrl+w"],
+        "clear",
+    ),
+    (Context::EditColor, Action::Close, &["esc"], "close"),
+    (Context::Save, Action::Confirm, &["enter"], "save"),
+    (
+        Context::Save,
+        Action::SwitchField,
+        &["tab", "backtab"],
+        "name / tags",
+    ),
+    (Context::Save, Action::Close, &["esc"], "close"),
+    (Context::Files, Action::Up, &["up"], "previous file"),
+    (Context::Files, Action::Down, &["down"], "next file"),
+    (Context::Files, Action::Confirm, &["enter", "right"], "open"),
+    (
+        Context::Files,
+        Action::Parent,
+        &["backspace", "left"],
+        "parent folder",
+    ),
+    (Context::Files, Action::Close, &["esc", "q"], "close"),
+    (Context::Library, Action::Up, &["up"], "previous palette"),
+    (Context::Library, Action::Down, &["down"], "next palette"),
+    (Context::Library, Action::Confirm, &["enter"], "open"),
+    (Context::Library, Action::Close, &["esc"], "close"),
+    (Context::Seed, Action::Confirm, &["enter"], "generate"),
+    (Context::Seed, Action::Close, &["esc", "q", "r"], "close"),
+    (Context::History, Action::Up, &["up"], "newer"),
+    (Context::History, Action::Down, &["down"], "older"),
+    (Context::History, Action::Confirm, &["enter"], "go back to"),
+    (Context::History, Action::Close, &["esc", "q", "h"], "close"),
+    (
+        Context::Export,
+        Action::PreviousFormat,
+        &["left"],
+        "previous format",
+    ),
+    (
+        Context::Export,
+        Action::NextFormat,
+        &["right"],
+        "next format",
+    ),
+    (Context::Export, Action::Up, &["up"], "previous color"),
+    (Context::Export, Action::Down, &["down"], "next color"),
+    (Context::Export, Action::Confirm, &["enter"], "write file"),
+    (Context::Export, Action::Copy, &["ctrl+y"], "copy"),
+    (Context::Export, Action::Close, &["esc"], "close"),
+    (Context::TerminalTheme, Action::Export, &["e"], "export"),
+    (
+        Context::TerminalTheme,
+        Action::Close,
+        &["esc", "q", "t"],
+        "close",
+    ),
+    (Context::Sliders, Action::Up, &["up"], "previous channel"),
+    (Context::Sliders, Action::Down, &["down"], "next channel"),
+    (Context::Sliders, Action::Decrease, &["left"], "decrease"),
+    (Context::Sliders, Action::Increase, &["right"], "increase"),
+    (
+        Context::Sliders,
+        Action::DecreaseCoarse,
+        &["shift+left", "pagedown"],
+        "decrease more",
+    ),
+    (
+        Context::Sliders,
+        Action::IncreaseCoarse,
+        &["shift+right", "pageup"],
+        "increase more",
+    ),
+    (
+        Context::Sliders,
+        Action::NextMode,
+        &["tab"],
+        "HSV / RGB / OKLCH",
+    ),
+    (
+        Context::Sliders,
+        Action::Close,
+        &["esc", "enter", "n"],
+        "close",
+    ),
+    // '?' would be typed into the text fields
+    (Context::Theories, Action::Help, &["?", "f1"], "keys"),
+    (Context::EditColor, Action::Help, &["f1"], "keys"),
+    (Context::Save, Action::Help, &["f1"], "keys"),
+    (Context::Files, Action::Help, &["?", "f1"], "keys"),
+    (Context::Library, Action::Help, &["f1"], "keys"),
+    (Context::Seed, Action::Help, &["?", "f1"], "keys"),
+    (Context::History, Action::Help, &["?", "f1"], "keys"),
+    (Context::Export, Action::Help, &["f1"], "keys"),
+    (Context::TerminalTheme, Action::Help, &["?", "f1"], "keys"),
+    (Context::Sliders, Action::Help, &["?", "f1"], "keys"),
+];
+
+// what the status bar hints at on each page, the keys come from the keymap
+const HINTS: &[(Context, &[(Action, &str)])] = &[
+    (
+        Context::Main,
+        &[
+            (Action::Generate, "generate"),
+            (Action::ToggleLock, "lock"),
+            (Action::Theories, "theory"),
+            (Action::EditColor, "edit"),
+            (Action::Sliders, "adjust"),
+            (Action::Undo, "undo"),
+            (Action::Help, "all keys"),
+            (Action::Quit, "quit"),
+        ],
+    ),
+    (
+        Context::Theories,
+        &[
+            (Action::Confirm, "use"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::EditColor,
+        &[
+            (Action::Confirm, "apply"),
+            (Action::Clear, "clear"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::Save,
+        &[
+            (Action::Confirm, "save"),
+            (Action::SwitchField, "name / tags"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::Files,
+        &[
+            (Action::Confirm, "open"),
+            (Action::Parent, "parent folder"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::Library,
+        &[
+            (Action::Confirm, "open"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::Seed,
+        &[
+            (Action::Confirm, "generate"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::History,
+        &[
+            (Action::Confirm, "go back to"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::Export,
+        &[
+            (Action::NextFormat, "format"),
+            (Action::Down, "color"),
+            (Action::Confirm, "write"),
+            (Action::Copy, "copy"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::TerminalTheme,
+        &[
+            (Action::Export, "export"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+    (
+        Context::Sliders,
+        &[
+            (Action::Down, "channel"),
+            (Action::Increase, "nudge"),
+            (Action::NextMode, "mode"),
+            (Action::Close, "close"),
+            (Action::Help, "keys"),
+        ],
+    ),
+];
+
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULTS
+            .iter()
+            .map(|(context, action, keys, description)| Binding {
+                context: *context,
+                action: *action,
+                keys: keys.iter().map(|key| key.parse().unwrap()).collect(),
+                description,
+            })
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    pub fn action(&self, context: Context, key_event: &KeyEvent) -> Option<Action> {
+        self.bindings(context)
+            .find(|binding| binding.keys.iter().any(|key| key.matches(key_event)))
+            .map(|binding| binding.action)
+    }
+
+    /// Every action on a page, in the order the help lists them.
+    pub fn bindings(&self, context: Context) -> impl Iterator<Item = &Binding> {
+        self.bindings
+            .iter()
+            .filter(move |binding| binding.context == context)
+    }
+
+    /// The first key bound to `action`, for messages that name it.
+    pub fn key(&self, context: Context, action: Action) -> Option<KeyBinding> {
+        self.bindings(context)
+            .find(|binding| binding.action == action)?
+            .keys
+            .first()
+            .copied()
+    }
+
+    /// The first key of each action worth hinting at on a page, skipping
+    /// unbound ones.
+    pub fn hints(&self, context: Context) -> Vec<(KeyBinding, &'static str)> {
+        let hints = HINTS
+            .iter()
+            .find(|(c, _)| *c == context)
+            .map_or(&[][..], |(_, hints)| hints);
+
+        hints
+            .iter()
+            .filter_map(|(action, label)| Some((self.key(context, *action)?, *label)))
+            .collect()
+    }
+
+    fn binding_mut(&mut self, context: Context, action: Action) -> Option<&mut Binding> {
+        self.bindings
+            .iter_mut()
+            .find(|binding| binding.context == context && binding.action == action)
+    }
+
+    /// Keys bound to more than one action on the same page.
+    fn conflicts(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+
+        for (i, binding) in self.bindings.iter().enumerate() {
+            for other in &self.bindings[i + 1..] {
+                if other.context != binding.context {
+                    continue;
+                }
+
+                for key in binding.keys.iter().filter(|key| other.keys.contains(key)) {
+                    errors.push(ConfigError {
+                        key: format!("{}.{}", binding.context.name(), binding.action.name()),
+                        message: format!("\"{key}\" is also bound to {}", other.action.name()),
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+pub fn keymap_path() -> PathBuf {
+    config::config_dir().join(KEYMAP_FILE_NAME)
+}
+
+fn parse_keys(value: &Value) -> Result<Vec<KeyBinding>, String> {
+    let keys = match value {
+        Value::String(key) => vec![key.as_str()],
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().ok_or("expected key names"))
+            .collect::<Result<_, _>>()?,
+        _ => return Err(String::from("expected a key or a list of keys")),
+    };
+
+    keys.into_iter().map(str::parse).collect()
+}
+
+/// The defaults with the bindings in `text` applied. Bad entries are
+/// skipped, conflicts throw the whole file out.
+pub fn parse(text: &str) -> (Keymap, Vec<ConfigError>) {
+    let mut keymap = Keymap::default();
+
+    let table: toml::Table = match toml::from_str(text) {
+        Ok(table) => table,
+        Err(err) => {
+            return (
+                keymap,
+                vec![ConfigError {
+                    key: String::new(),
+                    message: err.message().to_string(),
+                }],
+            );
+        }
+    };
+
+    let mut errors = Vec::new();
+
+    for (context_name, actions) in &table {
+        let error = |key: String, message: &str| ConfigError {
+            key,
+            message: message.to_string(),
+        };
+
+        let Some(context) = Context::parse(context_name) else {
+            errors.push(error(context_name.clone(), "unknown page"));
+            continue;
+        };
+
+        let Value::Table(actions) = actions else {
+            errors.push(error(context_name.clone(), "expected a table of actions"));
+            continue;
+        };
+
+        for (action_name, value) in actions {
+            let key = format!("{context_name}.{action_name}");
+
+            let Some(binding) =
+                Action::parse(action_name).and_then(|action| keymap.binding_mut(context, action))
+            else {
+                errors.push(error(key, "unknown action on this page"));
+                continue;
+            };
+
+            match parse_keys(value) {
+                Ok(keys) => binding.keys = keys,
+                Err(message) => errors.push(error(key, &message)),
+            }
+        }
+    }
+
+    let conflicts = keymap.conflicts();
+    if !conflicts.is_empty() {
+        errors.extend(conflicts);
+        return (Keymap::default(), errors);
+    }
+
+    (keymap, errors)
+}
+
+/// Loads `path` over the defaults. A missing file is fine.
+pub fn load(path: &Path) -> (Keymap, Vec<ConfigError>) {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => (Keymap::default(), Vec::new()),
+        Err(err) => (
+            Keymap::default(),
+            vec![ConfigError {
+                key: String::new(),
+                message: err.to_string(),
+            }],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn messages(errors: &[ConfigError]) -> Vec<String> {
+        errors.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        assert!(messages(&Keymap::default().conflicts()).is_empty());
+    }
+
+    #[test]
+    fn key_names_round_trip() {
+        for text in [
+            "a",
+            "A",
+            "ctrl+r",
+            "alt+1",
+            "shift+left",
+            "space",
+            "f1",
+            "pagedown",
+            "+",
+        ] {
+            let key: KeyBinding = text.parse().unwrap();
+            assert_eq!(key.to_string(), text);
+        }
+
+        assert_eq!("shift+a".parse::<KeyBinding>().unwrap().to_string(), "A");
+        assert_eq!(
+            "ctrl++".parse::<KeyBinding>().unwrap().to_string(),
+            "ctrl++"
+        );
+        assert_eq!(
+            "shift+tab".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::BackTab, KeyModifiers::NONE))
+        );
+    }
+
+    #[test]
+    fn key_name_errors() {
+        assert_eq!(
+            "super+a".parse::<KeyBinding>(),
+            Err(String::from("unknown modifier \"super\" in \"super+a\""))
+        );
+        assert_eq!(
+            "ctrl+nope".parse::<KeyBinding>(),
+            Err(String::from("unknown key \"nope\""))
+        );
+    }
+
+    #[test]
+    fn rebinds_actions() {
+        let (keymap, errors) =
+            parse("[main]\nselect-next = [\"l\", \"right\"]\ntoggle-lock = \"L\"\n");
+
+        assert!(errors.is_empty(), "{:?}", messages(&errors));
+        assert_eq!(
+            keymap.action(
+                Context::Main,
+                &press(KeyCode::Char('l'), KeyModifiers::NONE)
+            ),
+            Some(Action::SelectNext)
+        );
+        assert_eq!(
+            keymap.action(
+                Context::Main,
+                &press(KeyCode::Char('L'), KeyModifiers::SHIFT)
+            ),
+            Some(Action::ToggleLock)
+        );
+    }
+
+    #[test]
+    fn skips_bad_entries() {
+        let text = r#"
+            nowhere = { quit = "q" }
+            theories = "x"
+
+            [main]
+            fly = "f"
+            lock-block-10 = "alt+0"
+            quit = 1
+            undo = ["ctrl+nope"]
+            redo = "ctrl+y"
+        "#;
+        let (keymap, errors) = parse(text);
+
+        assert_eq!(
+            messages(&errors),
+            [
+                "main.fly: unknown action on this page",
+                "main.lock-block-10: unknown action on this page",
+                "main.quit: expected a key or a list of keys",
+                "main.undo: unknown key \"nope\"",
+                "nowhere: unknown page",
+                "theories: expected a table of actions",
+            ]
+        );
+
+        // the good entry still applies, the broken ones keep their defaults
+        let key = |code| press(code, KeyModifiers::NONE);
+        assert_eq!(
+            keymap.action(
+                Context::Main,
+                &press(KeyCode::Char('y'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::Redo)
+        );
+        assert_eq!(
+            keymap.action(Context::Main, &key(KeyCode::Char('q'))),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(Context::Main, &key(KeyCode::Char('u'))),
+            Some(Action::Undo)
+        );
+    }
+
+    #[test]
+    fn conflicts_reject_the_file() {
+        let (keymap, errors) = parse("[main]\nselect-next = \"l\"\nundo = \"right\"\n");
+
+        assert_eq!(
+            messages(&errors),
+            ["main.select-next: \"l\" is also bound to toggle-lock"]
+        );
+        assert_eq!(
+            keymap.action(
+                Context::Main,
+                &press(KeyCode::Char('l'), KeyModifiers::NONE)
+            ),
+            Some(Action::ToggleLock)
+        );
+    }
+
+    #[test]
+    fn invalid_toml() {
+        let (_, errors) = parse("[main\n");
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].key.is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+use std::{fs, path::PathBuf};
+
+use crate::palette_file::{PALETTE_FILE_EXTENSION, PaletteFile};
+
+pub struct LibraryEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub file: PaletteFile,
+}
+
+impl LibraryEntry {
+    /// Search terms are matched case-insensitively:
+    /// `#tag` matches a tag, `@theory` matches the theory and anything
+    /// else has to be part of the name. All terms have to match.
+    pub fn matches(&self, query: &str) -> bool {
+        query.split_whitespace().all(|term| {
+            let term = term.to_lowercase();
+
+            if let Some(tag) = term.strip_prefix('#') {
+                self.file
+                    .tags
+                    .iter()
+                    .any(|t| t.to_lowercase().contains(tag))
+            } else if let Some(theory) = term.strip_prefix('@') {
+                self.file.theory.to_lowercase().starts_with(theory)
+            } else {
+                self.name.to_lowercase().contains(&term)
+            }
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct Library {
+    pub dir: PathBuf,
+    pub entries: Vec<LibraryEntry>,
+    /// Files that are in the library directory but failed to load.
+    pub broken: Vec<(PathBuf, String)>,
+}
+
+impl Library {
+    pub fn load(dir: PathBuf) -> Self {
+        let mut library = Self {
+            dir,
+            ..Default::default()
+        };
+
+        let Ok(read_dir) = fs::read_dir(&library.dir) else {
+            return library;
+        };
+
+        for path in read_dir.filter_map(|entry| entry.ok().map(|e| e.path())) {
+            if !path.is_file()
+                || path
+                    .extension()
+                    .is_none_or(|ext| ext != PALETTE_FILE_EXTENSION)
+            {
+                continue;
+            }
+
+            match PaletteFile::load(&path) {
+                Ok(file) => library.entries.push(LibraryEntry {
+                    name: path
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    path,
+                    file,
+                }),
+                Err(err) => library.broken.push((path, err.to_string())),
+            }
+        }
+
+        library
+            .entries
+            .sort_by_key(|entry| entry.name.to_lowercase());
+
+        library
+    }
+
+    pub fn filtered(&self, query: &str) -> Vec<&LibraryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.matches(query))
+            .collect()
+    }
+}
@@ -1,9 +1,31 @@
-use std::io;
+use std::{io, process};
+
+use clap::Parser;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+};
 
 mod app;
+mod cli;
+mod color_depth;
+mod color_palette;
+mod color_parser;
+mod config;
+mod contrast;
+mod cvd;
+mod export;
+mod generator;
+mod history;
+mod keymap;
+mod library;
+mod notification;
+mod palette_file;
+mod terminal_theme;
+mod theory;
 mod widgets;
 
-use crate::app::App;
+use crate::{app::App, cli::Cli, color_depth::ColorDepth};
 
 #[macro_export]
 macro_rules! margin {
@@ -15,9 +37,51 @@ macro_rules! margin {
     };
 }
 
+/// Mouse capture for as long as it's alive. Dropping it turns capture off,
+/// so a panic or an early return doesn't leave the shell getting mouse codes.
+struct MouseCapture;
+
+impl MouseCapture {
+    fn enable() -> io::Result<Self> {
+        execute!(io::stdout(), EnableMouseCapture)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for MouseCapture {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), DisableMouseCapture);
+    }
+}
+
 fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+
+    if let Some(command) = cli.command {
+        if let Err(err) = cli::run(command, cli.config.as_deref()) {
+            eprintln!("error: {err}");
+            process::exit(1);
+        }
+
+        return Ok(());
+    }
+
     let mut terminal = ratatui::init();
-    let app_result = App::default().run(&mut terminal);
+    let mouse_capture = match MouseCapture::enable() {
+        Ok(mouse_capture) => mouse_capture,
+        Err(err) => {
+            ratatui::restore();
+            return Err(err);
+        }
+    };
+
+    let mut app = App {
+        color_depth: cli.colors.unwrap_or_else(ColorDepth::detect),
+        ..App::new(cli.config.as_deref())
+    };
+    let app_result = app.run(&mut terminal);
+
+    drop(mouse_capture);
     ratatui::restore();
     app_result
 }
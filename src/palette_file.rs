@@ -0,0 +1,261 @@
+//! Palette files.
+//!
+//! A palette is stored as a small TOML document so it can be read, diffed and
+//! edited by hand:
+//!
+//! ```toml
+//! version = 2
+//! theory = "Triad"
+//! tags = ["brand", "dark"] # optional
+//! seed = 1234567890         # optional, the seed the palette was generated from
+//! color_space = "oklch"     # optional, "hsv" when missing
+//!
+//! [[blocks]]
+//! slot = 0          # position of the block, blocks are shown in slot order
+//! hue = 212.0       # degrees, 0..360
+//! saturation = 0.64 # 0..=1
+//! value = 0.71      # 0..=1
+//! locked = true
+//! name = "primary"  # optional, used when exporting
+//! ```
+//!
+//! `version` is bumped whenever the layout changes in a way older builds can't
+//! read. Files with a newer version are rejected instead of being half-loaded.
+//! Version 2 raised the block limit from 9 to 32, version 1 files are still
+//! read as they are.
+
+use std::{
+    collections::HashSet,
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    color_palette::{MAX_BLOCKS, MIN_BLOCKS},
+    generator::ColorSpace,
+    widgets::content::ColorBlock,
+};
+
+pub const PALETTE_FILE_VERSION: u32 = 2;
+// oldest version this build still reads
+const OLDEST_PALETTE_FILE_VERSION: u32 = 1;
+pub const PALETTE_FILE_EXTENSION: &str = "toml";
+
+#[derive(Debug)]
+pub enum PaletteFileError {
+    Io(io::Error),
+    Parse(String),
+    UnsupportedVersion(u32),
+    Invalid(String),
+}
+
+impl fmt::Display for PaletteFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteFileError::Io(err) => write!(f, "{err}"),
+            PaletteFileError::Parse(msg) => write!(f, "not a palette file: {msg}"),
+            PaletteFileError::UnsupportedVersion(v) => write!(
+                f,
+                "palette file version {v} is not supported (this build reads up to {PALETTE_FILE_VERSION})"
+            ),
+            PaletteFileError::Invalid(msg) => write!(f, "invalid palette: {msg}"),
+        }
+    }
+}
+
+impl From<io::Error> for PaletteFileError {
+    fn from(err: io::Error) -> Self {
+        PaletteFileError::Io(err)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PaletteFile {
+    pub version: u32,
+    /// Name of the theory, see [`crate::theory::TheoryRegistry`].
+    pub theory: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub color_space: ColorSpace,
+    pub blocks: Vec<BlockEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockEntry {
+    pub slot: usize,
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+}
+
+impl PaletteFile {
+    pub fn new(color_blocks: &[ColorBlock], theory: &str) -> Self {
+        let blocks = color_blocks
+            .iter()
+            .enumerate()
+            .map(|(slot, block)| {
+                let (hue, saturation, value) = block.get_hsv_values();
+                // rem_euclid rounds tiny negative hues up to exactly 360.0
+                let hue = hue.rem_euclid(360.0) % 360.0;
+
+                BlockEntry {
+                    slot,
+                    hue,
+                    saturation,
+                    value,
+                    locked: block.locked,
+                    name: block.name.clone(),
+                }
+            })
+            .collect();
+
+        Self {
+            version: PALETTE_FILE_VERSION,
+            theory: theory.to_string(),
+            tags: Vec::new(),
+            seed: None,
+            color_space: ColorSpace::default(),
+            blocks,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PaletteFileError> {
+        let text = fs::read_to_string(path)?;
+        Self::from_toml(&text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), PaletteFileError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, PaletteFileError> {
+        let table: toml::Table =
+            toml::from_str(text).map_err(|e| PaletteFileError::Parse(e.message().to_string()))?;
+
+        // check the version first so newer files get a useful error
+        // instead of whatever field happens to be missing
+        let version = match table.get("version") {
+            Some(toml::Value::Integer(v)) => u32::try_from(*v)
+                .map_err(|_| PaletteFileError::Invalid(format!("bad version {v}")))?,
+            Some(_) => return Err(PaletteFileError::Invalid("version must be a number".into())),
+            None => return Err(PaletteFileError::Invalid("missing version".into())),
+        };
+
+        if !(OLDEST_PALETTE_FILE_VERSION..=PALETTE_FILE_VERSION).contains(&version) {
+            return Err(PaletteFileError::UnsupportedVersion(version));
+        }
+
+        let file: PaletteFile = table
+            .try_into()
+            .map_err(|e: toml::de::Error| PaletteFileError::Parse(e.message().to_string()))?;
+
+        file.validate()?;
+        Ok(file)
+    }
+
+    pub fn to_toml(&self) -> Result<String, PaletteFileError> {
+        toml::to_string(self).map_err(|e| PaletteFileError::Parse(e.to_string()))
+    }
+
+    fn validate(&self) -> Result<(), PaletteFileError> {
+        if !(MIN_BLOCKS..=MAX_BLOCKS).contains(&self.blocks.len()) {
+            return Err(PaletteFileError::Invalid(format!(
+                "expected {MIN_BLOCKS} to {MAX_BLOCKS} blocks, found {}",
+                self.blocks.len()
+            )));
+        }
+
+        let mut seen_slots = HashSet::new();
+
+        for (i, block) in self.blocks.iter().enumerate() {
+            let n = i + 1;
+
+            if block.slot >= MAX_BLOCKS {
+                return Err(PaletteFileError::Invalid(format!(
+                    "block {n}: slot {} is out of range",
+                    block.slot
+                )));
+            }
+
+            if !seen_slots.insert(block.slot) {
+                return Err(PaletteFileError::Invalid(format!(
+                    "block {n}: slot {} is used twice",
+                    block.slot
+                )));
+            }
+
+            // 360 is the same as 0, older builds could write it
+            if !(0.0..=360.0).contains(&block.hue) {
+                return Err(PaletteFileError::Invalid(format!(
+                    "block {n}: hue {} is outside 0..=360",
+                    block.hue
+                )));
+            }
+
+            for (name, channel) in [("saturation", block.saturation), ("value", block.value)] {
+                if !(0.0..=1.0).contains(&channel) {
+                    return Err(PaletteFileError::Invalid(format!(
+                        "block {n}: {name} {channel} is outside 0..1"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The blocks in slot order, gaps between slots are closed.
+    pub fn color_blocks(&self) -> Vec<ColorBlock> {
+        let mut entries: Vec<&BlockEntry> = self.blocks.iter().collect();
+        entries.sort_by_key(|entry| entry.slot);
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let mut block = ColorBlock::new(entry.hue, entry.saturation, entry.value);
+                block.locked = entry.locked;
+                block.name = entry.name.clone();
+                block
+            })
+            .collect()
+    }
+}
+
+/// Directory palettes are saved to and the file picker opens in.
+pub fn palette_dir() -> PathBuf {
+    let data_dir = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    data_dir.join("terminal-palette").join("palettes")
+}
+
+/// Turns whatever the user typed into a path inside [`palette_dir`],
+/// adding the extension when it's missing.
+pub fn palette_path(name: &str) -> PathBuf {
+    let name = name.trim();
+
+    if Path::new(name)
+        .extension()
+        .is_some_and(|ext| ext == PALETTE_FILE_EXTENSION)
+    {
+        palette_dir().join(name)
+    } else {
+        palette_dir().join(format!("{name}.{PALETTE_FILE_EXTENSION}"))
+    }
+}
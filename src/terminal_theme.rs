@@ -0,0 +1,114 @@
+use palette::{FromColor, Hsv, RgbHue, Srgb};
+
+use crate::widgets::content::ColorBlock;
+
+pub const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+// hues the ANSI colors are expected to have, in ANSI order (red..cyan)
+const ANSI_HUES: [f32; 6] = [0.0, 120.0, 60.0, 240.0, 300.0, 180.0];
+
+pub type Rgb = (u8, u8, u8);
+
+pub struct TerminalTheme {
+    pub background: Rgb,
+    pub foreground: Rgb,
+    pub cursor: Rgb,
+    pub selection_background: Rgb,
+    /// color0..color15, normal colors first then the bright ones.
+    pub ansi: [Rgb; 16],
+}
+
+fn hsv_to_rgb(hue: f32, sat: f32, val: f32) -> Rgb {
+    let hsv = Hsv::new(
+        RgbHue::from_degrees(hue),
+        sat.clamp(0.0, 1.0),
+        val.clamp(0.0, 1.0),
+    );
+    let rgb: Srgb<f32> = Srgb::from_color(hsv);
+
+    (
+        (rgb.red * 255.0).round() as u8,
+        (rgb.green * 255.0).round() as u8,
+        (rgb.blue * 255.0).round() as u8,
+    )
+}
+
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let diff = (a - b).rem_euclid(360.0);
+    diff.min(360.0 - diff)
+}
+
+/// Half way between `target` and the block's hue, but never more than 20°
+/// away from `target` so the color keeps its meaning.
+fn tint_hue(block_hue: f32, target: f32) -> f32 {
+    let diff = (block_hue - target + 540.0).rem_euclid(360.0) - 180.0;
+    (target + (diff * 0.5).clamp(-20.0, 20.0)).rem_euclid(360.0)
+}
+
+pub fn hex(rgb: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2)
+}
+
+impl TerminalTheme {
+    /// Builds a dark theme around the palette.
+    ///
+    /// Background and foreground take the hue of the darkest block. Each ANSI
+    /// color starts from its expected hue and leans towards the closest block,
+    /// so red still reads as red but is tinted by the palette.
+    /// The most saturated block becomes the cursor.
+    pub fn from_blocks(color_blocks: &[ColorBlock]) -> Self {
+        let blocks: Vec<(f32, f32, f32)> = color_blocks
+            .iter()
+            .map(|block| {
+                let (h, s, v) = block.get_hsv_values();
+                (h.rem_euclid(360.0), s, v)
+            })
+            .collect();
+
+        let (base_hue, base_sat) = blocks
+            .iter()
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(h, s, _)| (*h, *s))
+            .unwrap_or((0.0, 0.0));
+
+        let background = hsv_to_rgb(base_hue, base_sat * 0.35, 0.12);
+        let foreground = hsv_to_rgb(base_hue, 0.08, 0.90);
+        let selection_background = hsv_to_rgb(base_hue, base_sat * 0.4, 0.30);
+
+        let cursor = blocks
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(h, s, _)| hsv_to_rgb(*h, s.max(0.5), 0.85))
+            .unwrap_or(foreground);
+
+        let mut ansi = [(0, 0, 0); 16];
+
+        ansi[0] = hsv_to_rgb(base_hue, base_sat * 0.3, 0.20);
+        ansi[7] = hsv_to_rgb(base_hue, 0.06, 0.75);
+        ansi[8] = hsv_to_rgb(base_hue, base_sat * 0.25, 0.42);
+        ansi[15] = foreground;
+
+        for (i, target) in ANSI_HUES.iter().enumerate() {
+            let (hue, sat) = blocks
+                .iter()
+                .min_by(|a, b| hue_distance(a.0, *target).total_cmp(&hue_distance(b.0, *target)))
+                .map(|(h, s, _)| (tint_hue(*h, *target), *s))
+                .unwrap_or((*target, 0.6));
+
+            let sat = sat.clamp(0.45, 0.8);
+
+            ansi[i + 1] = hsv_to_rgb(hue, sat, 0.78);
+            ansi[i + 9] = hsv_to_rgb(hue, sat - 0.1, 0.92);
+        }
+
+        Self {
+            background,
+            foreground,
+            cursor,
+            selection_background,
+            ansi,
+        }
+    }
+}
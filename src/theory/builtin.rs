@@ -0,0 +1,278 @@
+use palette::Hsv;
+use rand::Rng;
+
+use crate::{
+    generator::PaletteRng,
+    theory::{Base, ColorTheory, TheoryParameters, sample},
+};
+
+/// Where each block goes around the wheel, relative to the base hue.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Offsets {
+    /// Block `i` sits `i * step` degrees away.
+    Step(f32),
+    /// Blocks cycle through the listed offsets.
+    Cycle(Vec<f32>),
+}
+
+impl Offsets {
+    pub fn get(&self, i: usize) -> f32 {
+        match self {
+            Offsets::Step(step) => i as f32 * step,
+            Offsets::Cycle(offsets) if offsets.is_empty() => 0.0,
+            Offsets::Cycle(offsets) => offsets[i % offsets.len()],
+        }
+    }
+}
+
+/// A theory that places every block at a fixed hue offset from the base,
+/// which covers all the classic wheel harmonies.
+pub struct HueOffsets {
+    pub name: String,
+    pub description: String,
+    pub offsets: Offsets,
+    pub parameters: TheoryParameters,
+}
+
+impl HueOffsets {
+    pub fn new(
+        name: &str,
+        description: &str,
+        offsets: Offsets,
+        parameters: TheoryParameters,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            offsets,
+            parameters,
+        }
+    }
+}
+
+impl ColorTheory for HueOffsets {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters(&self) -> &TheoryParameters {
+        &self.parameters
+    }
+
+    fn parameters_mut(&mut self) -> &mut TheoryParameters {
+        &mut self.parameters
+    }
+
+    fn generate(
+        &self,
+        base: &Base,
+        locks: &[Option<Hsv>],
+        count: usize,
+        rng: &mut PaletteRng,
+    ) -> Vec<Hsv> {
+        (0..count)
+            .map(|i| match locks.get(i).copied().flatten() {
+                Some(locked) => locked,
+                None => base.rotate(self.offsets.get(i), &self.parameters, rng),
+            })
+            .collect()
+    }
+}
+
+// where block i sits between the first (0.0) and the last (1.0) block
+fn ramp_position(i: usize, count: usize) -> f32 {
+    if count < 2 {
+        0.5
+    } else {
+        (i as f32 / (count - 1) as f32).min(1.0)
+    }
+}
+
+/// One hue, dark to light, with the saturation wandering around.
+pub struct Monochromatic {
+    pub parameters: TheoryParameters,
+}
+
+impl ColorTheory for Monochromatic {
+    fn name(&self) -> &str {
+        "Monochromatic"
+    }
+
+    fn description(&self) -> &str {
+        "one hue from dark to light"
+    }
+
+    fn parameters(&self) -> &TheoryParameters {
+        &self.parameters
+    }
+
+    fn parameters_mut(&mut self) -> &mut TheoryParameters {
+        &mut self.parameters
+    }
+
+    fn generate(
+        &self,
+        base: &Base,
+        locks: &[Option<Hsv>],
+        count: usize,
+        rng: &mut PaletteRng,
+    ) -> Vec<Hsv> {
+        let (low, high) = self.parameters.value;
+
+        (0..count)
+            .map(|i| {
+                if let Some(locked) = locks.get(i).copied().flatten() {
+                    return locked;
+                }
+
+                let randomness = self.parameters.randomness;
+                let drift = if randomness > 0.0 {
+                    rng.random_range(-randomness..randomness)
+                } else {
+                    0.0
+                };
+
+                let level = low + ramp_position(i, count) * (high - low);
+                let jitter = self.parameters.jitter;
+                let level = if jitter > 0.0 {
+                    level + rng.random_range(-jitter..jitter)
+                } else {
+                    level
+                };
+
+                base.shade(drift, sample(self.parameters.saturation, rng), level)
+            })
+            .collect()
+    }
+}
+
+/// Shades of the base color on one end, tints on the other.
+pub struct Shades {
+    pub parameters: TheoryParameters,
+}
+
+impl ColorTheory for Shades {
+    fn name(&self) -> &str {
+        "Shades"
+    }
+
+    fn description(&self) -> &str {
+        "shades towards black, then tints towards white"
+    }
+
+    fn parameters(&self) -> &TheoryParameters {
+        &self.parameters
+    }
+
+    fn parameters_mut(&mut self) -> &mut TheoryParameters {
+        &mut self.parameters
+    }
+
+    fn generate(
+        &self,
+        base: &Base,
+        locks: &[Option<Hsv>],
+        count: usize,
+        rng: &mut PaletteRng,
+    ) -> Vec<Hsv> {
+        let (low, high) = self.parameters.value;
+        let base_saturation = if base.from_locks {
+            base.saturation
+        } else {
+            sample(self.parameters.saturation, rng)
+        };
+
+        (0..count)
+            .map(|i| {
+                if let Some(locked) = locks.get(i).copied().flatten() {
+                    return locked;
+                }
+
+                let t = ramp_position(i, count);
+
+                // first half darkens towards black, second half fades the
+                // saturation out towards white, the middle is the pure color
+                let level = low + (high - low) * (t * 2.0).min(1.0);
+                let saturation = base_saturation * (1.0 - ((t - 0.5) * 1.7).max(0.0));
+
+                base.shade(0.0, saturation, level)
+            })
+            .collect()
+    }
+}
+
+fn params(randomness: f32, saturation: (f32, f32), value: (f32, f32)) -> TheoryParameters {
+    TheoryParameters {
+        randomness,
+        saturation,
+        value,
+        ..Default::default()
+    }
+}
+
+pub fn theories() -> Vec<Box<dyn ColorTheory>> {
+    vec![
+        Box::new(HueOffsets::new(
+            "Analogous",
+            "neighbours 30° apart",
+            Offsets::Step(30.0),
+            params(10.0, (0.5, 0.8), (0.5, 0.8)),
+        )),
+        Box::new(HueOffsets::new(
+            "Complementary",
+            "the base and its opposite",
+            Offsets::Cycle(vec![0.0, 180.0]),
+            params(15.0, (0.5, 0.8), (0.5, 0.8)),
+        )),
+        Box::new(HueOffsets::new(
+            "Triad",
+            "three hues 120° apart",
+            Offsets::Cycle(vec![0.0, 120.0, 240.0]),
+            params(8.0, (0.6, 0.85), (0.55, 0.8)),
+        )),
+        Box::new(HueOffsets::new(
+            "Square",
+            "four hues 90° apart",
+            Offsets::Cycle(vec![0.0, 90.0, 180.0, 270.0]),
+            params(8.0, (0.55, 0.8), (0.5, 0.75)),
+        )),
+        Box::new(HueOffsets::new(
+            "SplitComplementary",
+            "the base and both sides of its opposite",
+            Offsets::Cycle(vec![0.0, 150.0, 210.0]),
+            params(10.0, (0.55, 0.85), (0.5, 0.8)),
+        )),
+        Box::new(HueOffsets::new(
+            "Rectangle",
+            "two opposite pairs 60° apart",
+            Offsets::Cycle(vec![0.0, 60.0, 180.0, 240.0]),
+            params(8.0, (0.55, 0.8), (0.5, 0.75)),
+        )),
+        Box::new(Monochromatic {
+            parameters: TheoryParameters {
+                randomness: 4.0,
+                saturation: (0.35, 0.9),
+                value: (0.3, 0.95),
+                jitter: 0.04,
+            },
+        }),
+        Box::new(HueOffsets::new(
+            "Compound",
+            "a neighbour pair and their opposites",
+            Offsets::Cycle(vec![0.0, 30.0, 180.0, 150.0]),
+            params(10.0, (0.5, 0.8), (0.5, 0.8)),
+        )),
+        Box::new(Shades {
+            parameters: TheoryParameters {
+                randomness: 0.0,
+                saturation: (0.6, 0.9),
+                value: (0.2, 1.0),
+                jitter: 0.0,
+            },
+        }),
+    ]
+}
@@ -0,0 +1,260 @@
+//! Theories defined by the user in `theories.toml`:
+//!
+//! ```toml
+//! [[theory]]
+//! name = "Muted split"
+//! description = "split complementary, low saturation"  # optional
+//! offsets = [0, 150, 210]     # degrees from the base hue, cycled through
+//! sat = "0.4..0.6"            # optional, also [0.4, 0.6]
+//! val = "0.5..0.8"            # optional
+//! randomness = 6              # optional, degrees of hue drift either way
+//! jitter = 0.05               # optional, drift around locked blocks
+//! ```
+//!
+//! A broken entry is skipped and reported, the others still load.
+
+use std::{fs, io, path::Path, path::PathBuf};
+
+use toml::Value;
+
+use crate::{
+    config::{self, ConfigError},
+    theory::{
+        TheoryParameters, TheoryRegistry,
+        builtin::{HueOffsets, Offsets},
+    },
+};
+
+pub const THEORIES_FILE_NAME: &str = "theories.toml";
+
+/// Keys that tune a theory, also accepted in `config.toml`.
+pub const PARAMETER_KEYS: [&str; 4] = ["sat", "val", "randomness", "jitter"];
+
+const KNOWN_KEYS: [&str; 7] = [
+    "name",
+    "description",
+    "offsets",
+    "sat",
+    "val",
+    "randomness",
+    "jitter",
+];
+
+pub fn theories_path() -> PathBuf {
+    config::config_dir().join(THEORIES_FILE_NAME)
+}
+
+fn number(value: &Value) -> Option<f32> {
+    match value {
+        Value::Float(v) => Some(*v as f32),
+        Value::Integer(v) => Some(*v as f32),
+        _ => None,
+    }
+}
+
+fn parse_range(value: &Value) -> Result<(f32, f32), String> {
+    let bounds = match value {
+        Value::String(text) => text
+            .split_once("..")
+            .and_then(|(low, high)| Some((low.trim().parse().ok()?, high.trim().parse().ok()?))),
+        Value::Array(items) if items.len() == 2 => number(&items[0]).zip(number(&items[1])),
+        _ => None,
+    };
+
+    let (low, high) = bounds.ok_or("expected \"min..max\" or [min, max]")?;
+
+    if !(0.0..=1.0).contains(&low) || !(0.0..=1.0).contains(&high) {
+        return Err(String::from("bounds have to be between 0 and 1"));
+    }
+
+    if low > high {
+        return Err(format!("{low} is bigger than {high}"));
+    }
+
+    Ok((low, high))
+}
+
+/// Reads the `sat`, `val`, `randomness` and `jitter` keys of `table` into
+/// `parameters`, leaving missing ones alone.
+pub fn parse_parameters(
+    table: &toml::Table,
+    key: &str,
+    parameters: &mut TheoryParameters,
+) -> Result<(), ConfigError> {
+    let error = |field: &str, message: String| ConfigError {
+        key: format!("{key}.{field}"),
+        message,
+    };
+
+    if let Some(value) = table.get("sat") {
+        parameters.saturation = parse_range(value).map_err(|message| error("sat", message))?;
+    }
+
+    if let Some(value) = table.get("val") {
+        parameters.value = parse_range(value).map_err(|message| error("val", message))?;
+    }
+
+    if let Some(value) = table.get("randomness") {
+        parameters.randomness = number(value)
+            .filter(|degrees| (0.0..=180.0).contains(degrees))
+            .ok_or_else(|| {
+                error(
+                    "randomness",
+                    String::from("expected degrees between 0 and 180"),
+                )
+            })?;
+    }
+
+    if let Some(value) = table.get("jitter") {
+        parameters.jitter = number(value)
+            .filter(|amount| (0.0..=0.5).contains(amount))
+            .ok_or_else(|| {
+                error(
+                    "jitter",
+                    String::from("expected a number between 0 and 0.5"),
+                )
+            })?;
+    }
+
+    Ok(())
+}
+
+fn parse_theory(table: &toml::Table, key: &str) -> Result<HueOffsets, ConfigError> {
+    let error = |field: &str, message: String| ConfigError {
+        key: format!("{key}.{field}"),
+        message,
+    };
+
+    if let Some(unknown) = table.keys().find(|k| !KNOWN_KEYS.contains(&k.as_str())) {
+        return Err(error(unknown, String::from("unknown key")));
+    }
+
+    let name = match table.get("name") {
+        Some(Value::String(name)) if !name.trim().is_empty() => name.trim().to_string(),
+        Some(_) => return Err(error("name", String::from("expected a non-empty string"))),
+        None => return Err(error("name", String::from("missing"))),
+    };
+
+    let description = match table.get("description") {
+        Some(Value::String(description)) => description.clone(),
+        Some(_) => return Err(error("description", String::from("expected a string"))),
+        None => String::from("custom theory"),
+    };
+
+    let offsets = match table.get("offsets") {
+        Some(Value::Array(items)) if !items.is_empty() => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                number(item)
+                    .map(|offset| offset.rem_euclid(360.0))
+                    .ok_or_else(|| {
+                        error(&format!("offsets[{i}]"), String::from("expected degrees"))
+                    })
+            })
+            .collect::<Result<Vec<f32>, _>>()?,
+        Some(_) => {
+            return Err(error(
+                "offsets",
+                String::from("expected a non-empty list of degrees"),
+            ));
+        }
+        None => return Err(error("offsets", String::from("missing"))),
+    };
+
+    let mut parameters = TheoryParameters::default();
+    parse_parameters(table, key, &mut parameters)?;
+
+    Ok(HueOffsets::new(
+        &name,
+        &description,
+        Offsets::Cycle(offsets),
+        parameters,
+    ))
+}
+
+/// Parses every `[[theory]]` table, keeping the good ones along with their key.
+pub fn parse(text: &str) -> (Vec<(String, HueOffsets)>, Vec<ConfigError>) {
+    let table: toml::Table = match toml::from_str(text) {
+        Ok(table) => table,
+        Err(err) => {
+            return (
+                Vec::new(),
+                vec![ConfigError {
+                    key: String::new(),
+                    message: err.message().to_string(),
+                }],
+            );
+        }
+    };
+
+    let mut theories = Vec::new();
+    let mut errors = Vec::new();
+
+    for key in table.keys().filter(|key| key.as_str() != "theory") {
+        errors.push(ConfigError {
+            key: key.clone(),
+            message: String::from("unknown key, theories go in [[theory]] tables"),
+        });
+    }
+
+    let entries = match table.get("theory") {
+        Some(Value::Array(entries)) => entries.as_slice(),
+        Some(_) => {
+            errors.push(ConfigError {
+                key: String::from("theory"),
+                message: String::from("expected [[theory]] tables"),
+            });
+            &[]
+        }
+        None => &[],
+    };
+
+    for (i, entry) in entries.iter().enumerate() {
+        let key = format!("theory[{i}]");
+
+        match entry {
+            Value::Table(entry) => match parse_theory(entry, &key) {
+                Ok(theory) => theories.push((key, theory)),
+                Err(err) => errors.push(err),
+            },
+            _ => errors.push(ConfigError {
+                key,
+                message: String::from("expected a table"),
+            }),
+        }
+    }
+
+    (theories, errors)
+}
+
+/// Adds the theories from `path` to `registry`. A missing file is fine,
+/// names that are already taken are reported and skipped.
+pub fn register_from(registry: &mut TheoryRegistry, path: &Path) -> Vec<ConfigError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Vec::new(),
+        Err(err) => {
+            return vec![ConfigError {
+                key: String::new(),
+                message: err.to_string(),
+            }];
+        }
+    };
+
+    let (theories, mut errors) = parse(&text);
+
+    for (key, theory) in theories {
+        if registry.get(&theory.name).is_some() {
+            errors.push(ConfigError {
+                key: format!("{key}.name"),
+                message: format!("\"{}\" is already taken", theory.name),
+            });
+            continue;
+        }
+
+        registry.register(Box::new(theory));
+    }
+
+    errors
+}
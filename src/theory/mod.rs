@@ -0,0 +1,324 @@
+//! Color theories and the registry the editor and the CLI pick them from.
+//!
+//! A theory only decides where colors sit relative to a [`Base`] color. The
+//! base, the locked blocks and the random generator are handed to it by
+//! [`crate::generator::generate`], so adding a theory is implementing
+//! [`ColorTheory`] and passing it to [`TheoryRegistry::register`].
+
+pub mod builtin;
+pub mod custom;
+
+use palette::{FromColor, Hsv, IsWithinBounds, Oklch, Srgb};
+use rand::Rng;
+
+use crate::generator::{ColorSpace, PaletteRng};
+
+/// Theory new palettes start with.
+pub const DEFAULT_THEORY: &str = "Analogous";
+
+/// Knobs shared by most theories.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TheoryParameters {
+    /// How far (in degrees, either way) a hue may drift from its exact spot.
+    pub randomness: f32,
+    /// Saturation range for unlocked blocks when nothing is locked.
+    pub saturation: (f32, f32),
+    /// Value range for unlocked blocks when nothing is locked.
+    pub value: (f32, f32),
+    /// How far saturation and value may stray from the locked blocks' average.
+    pub jitter: f32,
+}
+
+impl Default for TheoryParameters {
+    fn default() -> Self {
+        Self {
+            randomness: 10.0,
+            saturation: (0.5, 0.8),
+            value: (0.5, 0.8),
+            jitter: 0.08,
+        }
+    }
+}
+
+pub trait ColorTheory {
+    /// Shown in the theory selector and stored in palette files.
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+
+    fn parameters(&self) -> &TheoryParameters;
+
+    /// For `config.toml` to change the ranges.
+    fn parameters_mut(&mut self) -> &mut TheoryParameters;
+
+    /// Colors for a palette of `count` blocks, in display order.
+    ///
+    /// `locks[i]` is the color of block `i` if it is locked. Whatever is
+    /// returned for a locked block is ignored, so implementations should skip
+    /// them without drawing from `rng` to keep seeds stable.
+    fn generate(
+        &self,
+        base: &Base,
+        locks: &[Option<Hsv>],
+        count: usize,
+        rng: &mut PaletteRng,
+    ) -> Vec<Hsv>;
+}
+
+/// The color a palette is built around: the average of the locked blocks, or
+/// a random color when nothing is locked.
+#[derive(Copy, Clone, Debug)]
+pub struct Base {
+    pub space: ColorSpace,
+    /// Degrees, HSV hue or OKLCH hue depending on `space`.
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+    /// OKLCH lightness and chroma every block shares in OKLCH mode.
+    pub lightness: f32,
+    pub chroma: f32,
+    /// Whether the base came from locked blocks.
+    pub from_locks: bool,
+}
+
+// circular mean, so 350° and 10° average to 0° and not 180°
+fn mean_hue(hues: impl Iterator<Item = f32>) -> f32 {
+    let (sin, cos) = hues.fold((0.0_f32, 0.0_f32), |(sin, cos), hue| {
+        let rad = hue.to_radians();
+        (sin + rad.sin(), cos + rad.cos())
+    });
+
+    sin.atan2(cos).to_degrees().rem_euclid(360.0)
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    sum / count.max(1) as f32
+}
+
+/// Uniform pick from `range`, which may be empty or reversed.
+pub fn sample(range: (f32, f32), rng: &mut PaletteRng) -> f32 {
+    let (low, high) = range;
+
+    if low < high {
+        rng.random_range(low..high)
+    } else {
+        low
+    }
+}
+
+// small variation around the locked blocks so unlocked ones don't all look the same
+fn jitter(base: f32, amount: f32, rng: &mut PaletteRng) -> f32 {
+    if amount <= 0.0 {
+        return base;
+    }
+
+    (base + rng.random_range(-amount..amount)).clamp(0.0, 1.0)
+}
+
+impl Base {
+    pub fn new(space: ColorSpace, locks: &[Option<Hsv>], rng: &mut PaletteRng) -> Self {
+        let locked: Vec<Hsv> = locks.iter().flatten().copied().collect();
+        let from_locks = !locked.is_empty();
+
+        let hsv: Hsv = if from_locks {
+            Hsv::new(
+                mean_hue(locked.iter().map(|c| c.hue.into_positive_degrees())),
+                mean(locked.iter().map(|c| c.saturation)),
+                mean(locked.iter().map(|c| c.value)),
+            )
+        } else {
+            Hsv::new(
+                rng.random_range(0.0..360.0),
+                rng.random_range(0.5..0.9),
+                rng.random_range(0.5..0.9),
+            )
+        };
+
+        let (hue, lightness, chroma) = match (space, from_locks) {
+            (ColorSpace::Hsv, _) => {
+                let oklch = Oklch::from_color(hsv);
+                (hsv.hue.into_positive_degrees(), oklch.l, oklch.chroma)
+            }
+            (ColorSpace::Oklch, true) => {
+                let oklch: Vec<Oklch> = locked.iter().map(|c| Oklch::from_color(*c)).collect();
+
+                (
+                    mean_hue(oklch.iter().map(|c| c.hue.into_positive_degrees())),
+                    mean(oklch.iter().map(|c| c.l)),
+                    mean(oklch.iter().map(|c| c.chroma)),
+                )
+            }
+            (ColorSpace::Oklch, false) => (
+                rng.random_range(0.0..360.0),
+                rng.random_range(0.62..0.78),
+                rng.random_range(0.09..0.16),
+            ),
+        };
+
+        Self {
+            space,
+            hue,
+            saturation: hsv.saturation,
+            value: hsv.value,
+            lightness,
+            chroma,
+            from_locks,
+        }
+    }
+
+    /// A color `offset` degrees around the wheel from the base.
+    ///
+    /// In HSV saturation and value come from the theory's ranges, or stay
+    /// close to the locked blocks. In OKLCH every color shares the base
+    /// lightness and chroma so none of them sticks out.
+    pub fn rotate(&self, offset: f32, params: &TheoryParameters, rng: &mut PaletteRng) -> Hsv {
+        let drift = if params.randomness > 0.0 {
+            rng.random_range(-params.randomness..params.randomness)
+        } else {
+            0.0
+        };
+        let hue = (self.hue + offset + drift).rem_euclid(360.0);
+
+        match self.space {
+            ColorSpace::Hsv => {
+                let (saturation, value) = if self.from_locks {
+                    (
+                        jitter(self.saturation, params.jitter, rng),
+                        jitter(self.value, params.jitter, rng),
+                    )
+                } else {
+                    (sample(params.saturation, rng), sample(params.value, rng))
+                };
+
+                Hsv::new(hue, saturation, value)
+            }
+            ColorSpace::Oklch => {
+                Hsv::from_color(gamut_map(Oklch::new(self.lightness, self.chroma, hue)))
+            }
+        }
+    }
+
+    /// The base hue at `level` (0 dark to 1 light): HSV value, or OKLCH
+    /// lightness with the base chroma. `saturation` is only used in HSV.
+    pub fn shade(&self, offset: f32, saturation: f32, level: f32) -> Hsv {
+        let hue = (self.hue + offset).rem_euclid(360.0);
+        let level = level.clamp(0.0, 1.0);
+
+        match self.space {
+            ColorSpace::Hsv => Hsv::new(hue, saturation.clamp(0.0, 1.0), level),
+            ColorSpace::Oklch => Hsv::from_color(gamut_map(Oklch::new(level, self.chroma, hue))),
+        }
+    }
+}
+
+/// Lowers chroma until the color fits in sRGB, keeping lightness and hue.
+pub fn gamut_map(oklch: Oklch) -> Srgb {
+    let mut color = oklch;
+    let mut low = 0.0;
+    let mut high = oklch.chroma;
+
+    if Srgb::from_color(color).is_within_bounds() {
+        return Srgb::from_color(color);
+    }
+
+    for _ in 0..20 {
+        color.chroma = (low + high) / 2.0;
+
+        if Srgb::from_color(color).is_within_bounds() {
+            low = color.chroma;
+        } else {
+            high = color.chroma;
+        }
+    }
+
+    color.chroma = low;
+    let rgb = Srgb::from_color(color);
+
+    // the search ends a hair inside the gamut but rounding can still spill over
+    Srgb::new(
+        rgb.red.clamp(0.0, 1.0),
+        rgb.green.clamp(0.0, 1.0),
+        rgb.blue.clamp(0.0, 1.0),
+    )
+}
+
+// lowercase without separators, so "split-complementary" finds "SplitComplementary"
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Every theory the editor knows about, in the order the selector lists them.
+pub struct TheoryRegistry {
+    theories: Vec<Box<dyn ColorTheory>>,
+}
+
+impl Default for TheoryRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            theories: Vec::new(),
+        };
+
+        for theory in builtin::theories() {
+            registry.register(theory);
+        }
+
+        registry
+    }
+}
+
+impl TheoryRegistry {
+    /// Adds `theory`, replacing one with the same name.
+    pub fn register(&mut self, theory: Box<dyn ColorTheory>) {
+        let key = normalize(theory.name());
+
+        match self
+            .theories
+            .iter()
+            .position(|t| normalize(t.name()) == key)
+        {
+            Some(idx) => self.theories[idx] = theory,
+            None => self.theories.push(theory),
+        }
+    }
+
+    /// Looks a theory up by name, ignoring case, dashes and underscores.
+    pub fn get(&self, name: &str) -> Option<&dyn ColorTheory> {
+        let key = normalize(name);
+
+        self.theories
+            .iter()
+            .find(|theory| normalize(theory.name()) == key)
+            .map(|theory| theory.as_ref())
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut (dyn ColorTheory + 'static)> {
+        let key = normalize(name);
+
+        self.theories
+            .iter_mut()
+            .find(|theory| normalize(theory.name()) == key)
+            .map(|theory| theory.as_mut())
+    }
+
+    /// Like [`TheoryRegistry::get`] but falls back to the first theory, for
+    /// palette files naming a theory that has since been removed.
+    pub fn get_or_first(&self, name: &str) -> &dyn ColorTheory {
+        self.get(name).unwrap_or(self.theories[0].as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn ColorTheory> {
+        self.theories.iter().map(|theory| theory.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.theories.len()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.iter().map(|theory| theory.name()).collect()
+    }
+}
@@ -1,27 +1,15 @@
-use rand::Rng;
-
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Stylize},
+    style::{Color, Style, Stylize},
     symbols::border,
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, Borders, Padding, Paragraph, Widget},
 };
 
 use palette::{FromColor, Hsv, RgbHue, Srgb};
 
-pub fn hex2rgb(hex: &str) -> (u8, u8, u8) {
-    let mut hex_owned = hex.to_string();
-    hex_owned.push_str("000000");
-    let padded = &hex_owned[..6];
-
-    let r = u8::from_str_radix(&padded[0..2], 16).unwrap();
-    let g = u8::from_str_radix(&padded[2..4], 16).unwrap();
-    let b = u8::from_str_radix(&padded[4..6], 16).unwrap();
-
-    (r, g, b)
-}
+use crate::{color_depth::ColorDepth, cvd::CvdMode};
 
 pub fn rgb2hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
     let r = r as f32 / 255.0;
@@ -54,9 +42,10 @@ pub fn rgb2hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
     (h, s, v)
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct ColorBlock {
-    pub block_id: usize,
+    /// Used for variable names and labels when exporting, empty if unnamed.
+    pub name: String,
 
     pub hsv: Hsv,
 
@@ -65,29 +54,20 @@ pub struct ColorBlock {
 }
 
 impl ColorBlock {
-    pub fn new(block_id: usize, hue: f32, sat: f32, val: f32) -> Self {
+    pub fn new(hue: f32, sat: f32, val: f32) -> Self {
         let hue = RgbHue::from_degrees(hue);
         let hsv: Hsv = Hsv::new(hue, sat, val);
 
         Self {
-            block_id: block_id,
+            name: String::new(),
 
-            hsv: hsv,
+            hsv,
 
             selected: false,
             locked: false,
         }
     }
 
-    pub fn generate_random_color(&mut self) {
-        let mut rng = rand::rng();
-        let hue = rng.random_range(0..360);
-        let sat = rng.random_range(50..90); // MIGHT GONNA EDIT THESE LATER
-        let val = rng.random_range(50..90);
-
-        self.change_color(hue as f32, sat as f32 / 100.0, val as f32 / 100.0);
-    }
-
     pub fn change_color(&mut self, hue: f32, sat: f32, val: f32) {
         let new_hue = RgbHue::from_degrees(hue);
         let hsv: Hsv = Hsv::new(new_hue, sat, val);
@@ -102,7 +82,7 @@ impl ColorBlock {
         let green = (rgb.green * 255.0).round() as u8;
         let blue = (rgb.blue * 255.0).round() as u8;
 
-        return (red, green, blue);
+        (red, green, blue)
     }
 
     pub fn get_hsv_values(&self) -> (f32, f32, f32) {
@@ -110,30 +90,33 @@ impl ColorBlock {
         let saturation: f32 = self.hsv.saturation;
         let value: f32 = self.hsv.value;
 
-        return (hue, saturation, value);
+        (hue, saturation, value)
     }
 
     pub fn get_hex(&self) -> String {
         let (r, g, b) = self.get_rgb_values();
         format!("#{r:02X}{g:02X}{b:02X}")
     }
+}
 
-    pub fn get_avg_hue(blocks: &Vec<Option<ColorBlock>>) -> f32 {
-        let mut hue_as_deg: f32 = 0.0;
-
-        for block in blocks.iter() {
-            let block = block.unwrap();
-
-            hue_as_deg += block.hsv.hue.into_degrees() as f32;
-        }
-
-        //return
-        hue_as_deg / blocks.len() as f32
+impl Widget for &ColorBlock {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.render_simulated(area, buf, CvdMode::Off, &[], None);
     }
 }
 
-impl Widget for ColorBlock {
-    fn render(self, area: Rect, buf: &mut Buffer) {
+impl ColorBlock {
+    /// Paints the block as seen under `cvd`. The labels keep the real values,
+    /// `confused_with` lists the (1 based) blocks this one can't be told from,
+    /// `ansi_index` is the palette entry a terminal without truecolor shows.
+    pub fn render_simulated(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        cvd: CvdMode,
+        confused_with: &[usize],
+        ansi_index: Option<u8>,
+    ) {
         let whole = Layout::default()
             .direction(Direction::Vertical)
             .constraints(vec![Constraint::Length(1), Constraint::Fill(1)])
@@ -145,7 +128,8 @@ impl Widget for ColorBlock {
         let (hue, saturation, value) = self.get_hsv_values();
         let (red, green, blue) = self.get_rgb_values();
 
-        let color = Color::Rgb(red, green, blue);
+        let (sim_red, sim_green, sim_blue) = cvd.simulate((red, green, blue));
+        let color = Color::Rgb(sim_red, sim_green, sim_blue);
 
         if self.selected {
             padding = selected_padding;
@@ -179,15 +163,32 @@ impl Widget for ColorBlock {
             block = selected_block;
         }
 
-        Paragraph::new(vec![
+        let mut lines = vec![
             Line::from(format!("HSV: {hue}, {:.2}, {:.2}", saturation, value)),
             Line::from(format!("RGB: {red}, {green}, {blue}")),
             Line::from(self.get_hex()),
             Line::from(""),
-        ])
-        .block(block)
-        .alignment(Alignment::Center)
-        .render(whole[1], buf);
+        ];
+
+        if let Some(index) = ansi_index {
+            lines.push(Line::from(format!("ANSI {index}")));
+        }
+
+        if cvd != CvdMode::Off {
+            lines.push(Line::from(format!(
+                "seen as #{sim_red:02X}{sim_green:02X}{sim_blue:02X}"
+            )));
+        }
+
+        if !confused_with.is_empty() {
+            let others: Vec<String> = confused_with.iter().map(|n| n.to_string()).collect();
+            lines.push(Line::from(format!("≈ block {}", others.join(", "))).bold());
+        }
+
+        Paragraph::new(lines)
+            .block(block)
+            .alignment(Alignment::Center)
+            .render(whole[1], buf);
 
         Paragraph::new(Line::from(lock_indicator_label))
             .block(lock_indicator_block)
@@ -196,42 +197,144 @@ impl Widget for ColorBlock {
     }
 }
 
+/// A row of small color cells, used wherever a whole palette is listed.
+pub fn swatch_strip(color_blocks: &[ColorBlock]) -> Vec<Span<'static>> {
+    color_blocks
+        .iter()
+        .map(|block| {
+            let (r, g, b) = block.get_rgb_values();
+            Span::from("   ").bg(Color::Rgb(r, g, b))
+        })
+        .collect()
+}
+
+// narrow enough for a handful of blocks on a laptop, wide enough for the labels
+const MIN_BLOCK_WIDTH: u16 = 18;
+
 pub struct MainContent {
-    pub color_blocks: [Option<ColorBlock>; 9],
+    pub color_blocks: Vec<ColorBlock>,
     pub selected_block_id: usize,
+    /// Block waiting to be swapped, see [`crate::color_palette::ColorPalette::marked`].
+    pub marked: Option<usize>,
+    /// First block on screen, see [`MainContent::scroll_to`].
+    pub scroll: usize,
+    pub cvd: CvdMode,
+    pub color_depth: ColorDepth,
 }
 
 impl MainContent {
-    pub fn new(color_blocks: [Option<ColorBlock>; 9], selected_block_id: usize) -> Self {
+    pub fn new(
+        color_blocks: Vec<ColorBlock>,
+        selected_block_id: usize,
+        marked: Option<usize>,
+        scroll: usize,
+        cvd: CvdMode,
+        color_depth: ColorDepth,
+    ) -> Self {
         Self {
-            color_blocks: color_blocks,
-            selected_block_id: selected_block_id,
+            color_blocks,
+            selected_block_id,
+            marked,
+            scroll,
+            cvd,
+            color_depth,
         }
     }
 }
 
-impl Widget for &mut MainContent {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let block_count = self.color_blocks.iter().filter(|b| b.is_some()).count();
+impl MainContent {
+    /// How many blocks fit side by side in `width` columns.
+    fn visible_count(width: u16) -> usize {
+        (width / MIN_BLOCK_WIDTH).max(1) as usize
+    }
 
-        let constraints: Vec<Constraint> = vec![Constraint::Fill(1); block_count];
+    /// Moves `scroll` as little as possible so block `selected` is on screen.
+    pub fn scroll_to(scroll: usize, selected: usize, block_count: usize, width: u16) -> usize {
+        let visible = Self::visible_count(width);
+
+        let scroll = if selected < scroll {
+            selected
+        } else if selected >= scroll + visible {
+            selected + 1 - visible
+        } else {
+            scroll
+        };
+
+        scroll.min(block_count.saturating_sub(visible))
+    }
+
+    /// The blocks on screen and where each goes, left to right.
+    pub fn block_areas(area: Rect, block_count: usize, scroll: usize) -> Vec<(usize, Rect)> {
+        let shown = block_count
+            .saturating_sub(scroll)
+            .min(Self::visible_count(area.width));
+        let constraints: Vec<Constraint> = vec![Constraint::Fill(1); shown];
 
         let layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(constraints)
             .split(area);
 
-        for (idx, block) in self
+        (scroll..scroll + shown)
+            .zip(layout.iter().copied())
+            .collect()
+    }
+}
+
+impl Widget for &mut MainContent {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let colors: Vec<(u8, u8, u8)> = self
             .color_blocks
-            .iter_mut()
-            .filter_map(|maybe| maybe.as_mut())
-            .enumerate()
-        {
-            // Mark selection
+            .iter()
+            .map(|block| block.get_rgb_values())
+            .collect();
+
+        let layout = MainContent::block_areas(area, self.color_blocks.len(), self.scroll);
+
+        for &(idx, block_area) in &layout {
+            let block = &mut self.color_blocks[idx];
             block.selected = idx == self.selected_block_id;
 
-            // Render into its packed layout slot
-            block.render(layout[idx], buf);
+            let confused_with: Vec<usize> = if self.cvd == CvdMode::Off {
+                Vec::new()
+            } else {
+                (0..colors.len())
+                    .filter(|other| *other != idx && self.cvd.confuses(colors[idx], colors[*other]))
+                    .map(|other| other + 1)
+                    .collect()
+            };
+
+            // the index of what's actually on screen, so after the simulation
+            let shown = self.cvd.simulate(colors[idx]);
+            let ansi_index = self.color_depth.nearest_index(shown);
+
+            block.render_simulated(block_area, buf, self.cvd, &confused_with, ansi_index);
+
+            if self.marked == Some(idx) {
+                buf.set_string(
+                    block_area.x,
+                    block_area.y,
+                    " ⇄ SWAP ",
+                    Style::new().reversed(),
+                );
+            }
+        }
+
+        // how many blocks are scrolled off either side, over the lock strip
+        let hidden_after = self
+            .color_blocks
+            .len()
+            .saturating_sub(self.scroll + layout.len());
+
+        if self.scroll > 0 {
+            let label = format!("◀ {}", self.scroll);
+            buf.set_string(area.x, area.y, label, Style::new().reversed());
+        }
+
+        if hidden_after > 0 {
+            let label = format!("{hidden_after} ▶");
+            let x = area.right().saturating_sub(label.chars().count() as u16);
+            buf.set_string(x, area.y, label, Style::new().reversed());
         }
     }
 }
@@ -0,0 +1,99 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Cell, Row, Table, Widget},
+};
+
+use crate::{
+    contrast::{WcagLevel, apca_lc, apca_usage, wcag_ratio},
+    widgets::content::ColorBlock,
+};
+
+fn level_color(level: WcagLevel) -> Color {
+    match level {
+        WcagLevel::Aaa => Color::LightGreen,
+        WcagLevel::Aa => Color::Green,
+        WcagLevel::AaLarge => Color::Yellow,
+        WcagLevel::Fail => Color::LightRed,
+    }
+}
+
+/// Every block as text on every other block as background.
+pub struct ContrastMatrix<'a> {
+    pub color_blocks: &'a [ColorBlock],
+}
+
+impl<'a> ContrastMatrix<'a> {
+    pub fn new(color_blocks: &'a [ColorBlock]) -> Self {
+        Self { color_blocks }
+    }
+}
+
+impl Widget for ContrastMatrix<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let colors: Vec<(u8, u8, u8)> = self
+            .color_blocks
+            .iter()
+            .map(|block| block.get_rgb_values())
+            .collect();
+
+        let swatch = |i: usize| {
+            let (r, g, b) = colors[i];
+            Span::from(format!(" {} ", i + 1)).bg(Color::Rgb(r, g, b))
+        };
+
+        let mut header = vec![Cell::from("text \\ bg")];
+        header.extend((0..colors.len()).map(|i| Cell::from(Line::from(swatch(i)))));
+
+        let rows: Vec<Row> = (0..colors.len())
+            .map(|text_idx| {
+                let mut cells = vec![Cell::from(Line::from(swatch(text_idx)))];
+
+                for bg_idx in 0..colors.len() {
+                    if text_idx == bg_idx {
+                        cells.push(Cell::from(Line::from("—").dim()));
+                        continue;
+                    }
+
+                    let (text, bg) = (colors[text_idx], colors[bg_idx]);
+                    let ratio = wcag_ratio(text, bg);
+                    let level = WcagLevel::from_ratio(ratio);
+                    let lc = apca_lc(text, bg);
+
+                    let sample = Span::styled(
+                        "Aa",
+                        Style::new()
+                            .fg(Color::Rgb(text.0, text.1, text.2))
+                            .bg(Color::Rgb(bg.0, bg.1, bg.2)),
+                    );
+
+                    cells.push(Cell::from(Text::from(vec![
+                        Line::from(vec![
+                            sample,
+                            Span::from(format!(" {ratio:.1} ")),
+                            Span::from(level.label()).fg(level_color(level)).bold(),
+                        ]),
+                        Line::from(format!("Lc {lc:.0} {}", apca_usage(lc))).dim(),
+                    ])));
+                }
+
+                Row::new(cells).height(2)
+            })
+            .collect();
+
+        let mut widths = vec![Constraint::Length(10)];
+        widths.extend(vec![Constraint::Min(14); colors.len()]);
+
+        Table::new(rows, widths)
+            .header(Row::new(header).bottom_margin(1))
+            .column_spacing(1)
+            .block(
+                Block::default()
+                    .title(" Contrast (WCAG 2.1 / APCA) ")
+                    .borders(Borders::TOP),
+            )
+            .render(area, buf);
+    }
+}
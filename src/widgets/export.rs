@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    export::{self, ExportFormat},
+    margin,
+    widgets::content::ColorBlock,
+};
+
+pub struct ExportPopup<'a> {
+    pub format: ExportFormat,
+    pub name: &'a str,
+    pub color_blocks: &'a [ColorBlock],
+    pub selected: usize,
+    pub output_path: &'a Path,
+    pub message: Option<&'a Result<String, String>>,
+}
+
+impl Widget for ExportPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(" Export ")
+            .title_bottom(" type to name the selected color ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(40), Constraint::Fill(1)])
+            .split(area.inner(margin!(2, 1)));
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::from("Format: "),
+                Span::from(format!("< {} >", self.format)).bold(),
+            ]),
+            Line::from(format!("File: {}", self.output_path.display())).dim(),
+            Line::from(""),
+            Line::from("Names:"),
+        ];
+
+        for (i, block) in self.color_blocks.iter().enumerate() {
+            let (r, g, b) = block.get_rgb_values();
+            let cursor = if i == self.selected { "_" } else { "" };
+
+            let mut line = Line::from(vec![
+                Span::from(if i == self.selected { "> " } else { "  " }),
+                Span::from("  ").bg(Color::Rgb(r, g, b)),
+                Span::from(format!(" {}{cursor}", block.name)),
+            ]);
+
+            if block.name.is_empty() && i != self.selected {
+                line.push_span(Span::from(format!("color-{}", i + 1)).dim());
+            }
+
+            lines.push(line);
+        }
+
+        match self.message {
+            Some(Ok(msg)) => {
+                lines.push(Line::from(""));
+                lines.push(Line::from(msg.as_str()).fg(Color::LightGreen));
+            }
+            Some(Err(msg)) => {
+                lines.push(Line::from(""));
+                lines.push(Line::from(msg.as_str()).fg(Color::LightRed));
+            }
+            None => {}
+        }
+
+        Paragraph::new(lines).render(columns[0], buf);
+
+        let preview = export::preview(self.format, self.name, self.color_blocks);
+
+        Paragraph::new(preview)
+            .block(Block::default().borders(Borders::LEFT).title(" Preview "))
+            .render(columns[1], buf);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget,
+        Widget,
+    },
+};
+
+use crate::margin;
+
+pub struct FilePicker {
+    pub title: &'static str,
+    pub dir: PathBuf,
+    pub extensions: &'static [&'static str],
+
+    pub entries: Vec<PathBuf>,
+    pub state: ListState,
+
+    pub error: Option<String>,
+}
+
+impl FilePicker {
+    pub fn new(title: &'static str, dir: PathBuf, extensions: &'static [&'static str]) -> Self {
+        let mut picker = Self {
+            title,
+            dir,
+            extensions,
+
+            entries: Vec::new(),
+            state: ListState::default(),
+
+            error: None,
+        };
+
+        picker.refresh();
+        picker
+    }
+
+    /// Re-reads the current directory. Sub directories come first, then files
+    /// with one of the picker's extensions, both sorted by name.
+    pub fn refresh(&mut self) {
+        self.entries.clear();
+        self.error = None;
+
+        let read_dir = match fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(err) => {
+                self.error = Some(format!("{}: {err}", self.dir.display()));
+                self.state.select(None);
+                return;
+            }
+        };
+
+        let (mut dirs, mut files): (Vec<PathBuf>, Vec<PathBuf>) = read_dir
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_dir()
+                    || path
+                        .extension()
+                        .is_some_and(|ext| self.extensions.iter().any(|e| ext == *e))
+            })
+            .partition(|path| path.is_dir());
+
+        dirs.sort();
+        files.sort();
+
+        self.entries.extend(dirs);
+        self.entries.extend(files);
+
+        if self.entries.is_empty() {
+            self.state.select(None);
+        } else {
+            self.state.select_first();
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Path> {
+        self.state
+            .selected()
+            .and_then(|idx| self.entries.get(idx))
+            .map(|path| path.as_path())
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    /// Steps into the selected directory. Returns the selected file if the
+    /// selection isn't a directory.
+    pub fn open_selected(&mut self) -> Option<PathBuf> {
+        let selected = self.selected()?.to_path_buf();
+
+        if selected.is_dir() {
+            self.dir = selected;
+            self.refresh();
+            None
+        } else {
+            Some(selected)
+        }
+    }
+
+    pub fn go_up(&mut self) {
+        if let Some(parent) = self.dir.parent() {
+            self.dir = parent.to_path_buf();
+            self.refresh();
+        }
+    }
+}
+
+impl Widget for &mut FilePicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(self.title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .split(area.inner(margin!(1, 1)));
+
+        Paragraph::new(Line::from(format!(" {}", self.dir.display())).dim()).render(layout[0], buf);
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                if path.is_dir() {
+                    ListItem::new(format!("{name}/")).bold()
+                } else {
+                    ListItem::new(name)
+                }
+            })
+            .collect();
+
+        let list = List::new(items).highlight_symbol(">");
+        StatefulWidget::render(list, layout[1], buf, &mut self.state);
+
+        if let Some(error) = &self.error {
+            Paragraph::new(Line::from(format!(" {error}")).fg(Color::LightRed))
+                .render(layout[2], buf);
+        }
+    }
+}
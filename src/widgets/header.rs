@@ -1,7 +1,6 @@
 use ratatui::{
     buffer::Buffer,
     style::{Color, Stylize},
-    symbols::border,
     text::Line,
     widgets::{Block, Padding, Paragraph, Widget},
 };
@@ -12,7 +11,7 @@ pub struct Header {
 
 impl Header {
     pub fn new(title: &'static str) -> Self {
-        Self { title: title }
+        Self { title }
     }
 }
 
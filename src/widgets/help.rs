@@ -0,0 +1,78 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Widget},
+};
+
+use crate::{
+    keymap::{Context, Keymap},
+    margin,
+};
+
+/// The effective bindings of one page, in as many columns as it takes.
+pub struct HelpOverlay<'a> {
+    pub keymap: &'a Keymap,
+    pub context: Context,
+}
+
+impl<'a> HelpOverlay<'a> {
+    pub fn new(keymap: &'a Keymap, context: Context) -> Self {
+        Self { keymap, context }
+    }
+}
+
+impl Widget for HelpOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(format!(" Keys: {} ", self.context.title()))
+            .title_bottom(" any key: close ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let inner = area.inner(margin!(2, 1));
+        if inner.height == 0 {
+            return;
+        }
+
+        let rows: Vec<(String, &str)> = self
+            .keymap
+            .bindings(self.context)
+            .map(|binding| {
+                let keys = if binding.keys.is_empty() {
+                    String::from("-")
+                } else {
+                    binding
+                        .keys
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+
+                (keys, binding.description)
+            })
+            .collect();
+
+        let per_column = inner.height as usize;
+        let columns = rows.len().div_ceil(per_column).max(1);
+        let column_width = inner.width / columns as u16;
+        let key_width = rows.iter().map(|(keys, _)| keys.len()).max().unwrap_or(0) + 2;
+
+        for (i, (keys, description)) in rows.iter().enumerate() {
+            let x = inner.x + (i / per_column) as u16 * column_width;
+            let y = inner.y + (i % per_column) as u16;
+
+            let line = Line::from(vec![
+                Span::from(format!("{keys:<key_width$}")).bold(),
+                Span::from(*description),
+            ]);
+
+            buf.set_line(x, y, &line, column_width.saturating_sub(1));
+        }
+    }
+}
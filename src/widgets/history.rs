@@ -0,0 +1,59 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget,
+        Widget,
+    },
+};
+
+use crate::{history::History, margin, widgets::content::swatch_strip};
+
+pub struct HistoryView<'a> {
+    pub history: &'a History,
+}
+
+impl<'a> HistoryView<'a> {
+    pub fn new(history: &'a History) -> Self {
+        Self { history }
+    }
+}
+
+impl StatefulWidget for HistoryView<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut ListState) {
+        let block = Block::default()
+            .title(" History ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let inner = area.inner(margin!(1, 1));
+
+        let items: Vec<ListItem> = self
+            .history
+            .entries()
+            .enumerate()
+            .map(|(i, snapshot)| {
+                let mut spans = vec![Span::from(format!("{:>3} ", i + 1)).dim()];
+                spans.extend(swatch_strip(&snapshot.palette));
+                spans.push(Span::from(format!("  before {}", snapshot.label)));
+
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        if items.is_empty() {
+            Paragraph::new(Line::from(" Nothing to undo yet").dim()).render(inner, buf);
+            return;
+        }
+
+        let list = List::new(items).highlight_symbol("> ");
+        StatefulWidget::render(list, inner, buf, state);
+    }
+}
@@ -0,0 +1,82 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget,
+        Widget,
+    },
+};
+
+use crate::{library::LibraryEntry, margin, widgets::content::swatch_strip};
+
+pub struct LibraryBrowser<'a> {
+    pub entries: Vec<&'a LibraryEntry>,
+    pub query: &'a str,
+    pub broken_count: usize,
+}
+
+impl<'a> LibraryBrowser<'a> {
+    pub fn new(entries: Vec<&'a LibraryEntry>, query: &'a str, broken_count: usize) -> Self {
+        Self {
+            entries,
+            query,
+            broken_count,
+        }
+    }
+}
+
+impl StatefulWidget for LibraryBrowser<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut ListState) {
+        let mut block = Block::default()
+            .title(" Library ")
+            .title_bottom(" type to search  #tag  @theory ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        if self.broken_count > 0 {
+            block = block.title(
+                Line::from(format!(" {} unreadable ", self.broken_count))
+                    .fg(Color::LightRed)
+                    .right_aligned(),
+            );
+        }
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(2), Constraint::Fill(1)])
+            .split(area.inner(margin!(1, 1)));
+
+        Paragraph::new(Line::from(format!(" Search: {}", self.query))).render(layout[0], buf);
+
+        if self.entries.is_empty() {
+            Paragraph::new(Line::from(" No palettes found").dim()).render(layout[1], buf);
+            return;
+        }
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut spans = swatch_strip(&entry.file.color_blocks());
+                spans.push(Span::from(format!(" {}", entry.name)).bold());
+                spans.push(Span::from(format!("  {}", entry.file.theory)).dim());
+
+                for tag in entry.file.tags.iter() {
+                    spans.push(Span::from(format!(" #{tag}")).fg(Color::Cyan));
+                }
+
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items).highlight_symbol("> ");
+        StatefulWidget::render(list, layout[1], buf, state);
+    }
+}
@@ -1,3 +1,11 @@
 pub mod content;
+pub mod contrast;
+pub mod export;
+pub mod file_picker;
 pub mod header;
+pub mod help;
+pub mod history;
+pub mod library;
+pub mod sliders;
 pub mod status_bar;
+pub mod terminal_preview;
@@ -0,0 +1,293 @@
+use palette::{FromColor, Hsv, Oklch, Srgb};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    contrast::relative_luminance, margin, theory::gamut_map, widgets::content::ColorBlock,
+};
+
+/// Which channels the slider editor shows.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum SliderMode {
+    #[default]
+    Hsv,
+    Rgb,
+    Oklch,
+}
+
+pub struct Channel {
+    pub label: &'static str,
+    pub min: f32,
+    pub max: f32,
+    pub fine: f32,
+    pub coarse: f32,
+    /// Hues go round, everything else stops at the ends.
+    pub wraps: bool,
+}
+
+const HSV_CHANNELS: [Channel; 3] = [
+    Channel {
+        label: "H",
+        min: 0.0,
+        max: 360.0,
+        fine: 1.0,
+        coarse: 15.0,
+        wraps: true,
+    },
+    Channel {
+        label: "S",
+        min: 0.0,
+        max: 1.0,
+        fine: 0.01,
+        coarse: 0.1,
+        wraps: false,
+    },
+    Channel {
+        label: "V",
+        min: 0.0,
+        max: 1.0,
+        fine: 0.01,
+        coarse: 0.1,
+        wraps: false,
+    },
+];
+
+const RGB_CHANNELS: [Channel; 3] = [
+    Channel {
+        label: "R",
+        min: 0.0,
+        max: 255.0,
+        fine: 1.0,
+        coarse: 16.0,
+        wraps: false,
+    },
+    Channel {
+        label: "G",
+        min: 0.0,
+        max: 255.0,
+        fine: 1.0,
+        coarse: 16.0,
+        wraps: false,
+    },
+    Channel {
+        label: "B",
+        min: 0.0,
+        max: 255.0,
+        fine: 1.0,
+        coarse: 16.0,
+        wraps: false,
+    },
+];
+
+const OKLCH_CHANNELS: [Channel; 3] = [
+    Channel {
+        label: "L",
+        min: 0.0,
+        max: 1.0,
+        fine: 0.01,
+        coarse: 0.1,
+        wraps: false,
+    },
+    Channel {
+        label: "C",
+        min: 0.0,
+        // a bit past the most saturated sRGB color
+        max: 0.37,
+        fine: 0.005,
+        coarse: 0.05,
+        wraps: false,
+    },
+    Channel {
+        label: "h",
+        min: 0.0,
+        max: 360.0,
+        fine: 1.0,
+        coarse: 15.0,
+        wraps: true,
+    },
+];
+
+impl SliderMode {
+    pub fn next(self) -> Self {
+        match self {
+            SliderMode::Hsv => SliderMode::Rgb,
+            SliderMode::Rgb => SliderMode::Oklch,
+            SliderMode::Oklch => SliderMode::Hsv,
+        }
+    }
+
+    pub fn channels(self) -> &'static [Channel; 3] {
+        match self {
+            SliderMode::Hsv => &HSV_CHANNELS,
+            SliderMode::Rgb => &RGB_CHANNELS,
+            SliderMode::Oklch => &OKLCH_CHANNELS,
+        }
+    }
+
+    pub fn values(self, hsv: Hsv) -> [f32; 3] {
+        match self {
+            SliderMode::Hsv => [hsv.hue.into_positive_degrees(), hsv.saturation, hsv.value],
+            SliderMode::Rgb => {
+                let rgb: Srgb<u8> = Srgb::from_color(hsv).into_format();
+                [rgb.red as f32, rgb.green as f32, rgb.blue as f32]
+            }
+            SliderMode::Oklch => {
+                let oklch = Oklch::from_color(hsv);
+                [oklch.l, oklch.chroma, oklch.hue.into_positive_degrees()]
+            }
+        }
+    }
+
+    /// `hsv` with one channel replaced. OKLCH colors outside sRGB lose
+    /// chroma until they fit.
+    pub fn with_channel(self, hsv: Hsv, channel: usize, value: f32) -> Hsv {
+        let mut values = self.values(hsv);
+        values[channel] = value;
+        let [a, b, c] = values;
+
+        // grays come back with hue 0, keep the old one so the hue slider doesn't jump
+        match self {
+            SliderMode::Hsv => Hsv::new(a, b, c),
+            SliderMode::Rgb => {
+                let mut out = Hsv::from_color(Srgb::new(a / 255.0, b / 255.0, c / 255.0));
+                if out.saturation == 0.0 {
+                    out.hue = hsv.hue;
+                }
+                out
+            }
+            SliderMode::Oklch => {
+                let mut out = Hsv::from_color(gamut_map(Oklch::new(a, b, c)));
+                if out.saturation == 0.0 {
+                    out.hue = hsv.hue;
+                }
+                out
+            }
+        }
+    }
+
+    /// Moves a channel by `steps` fine or coarse steps.
+    pub fn nudge(self, hsv: Hsv, channel: usize, steps: f32, coarse: bool) -> Hsv {
+        let spec = &self.channels()[channel];
+        let step = if coarse { spec.coarse } else { spec.fine };
+        let value = self.values(hsv)[channel] + steps * step;
+
+        let value = if spec.wraps {
+            value.rem_euclid(spec.max)
+        } else {
+            value.clamp(spec.min, spec.max)
+        };
+
+        self.with_channel(hsv, channel, value)
+    }
+}
+
+fn format_value(spec: &Channel, value: f32) -> String {
+    if spec.max > 1.0 {
+        format!("{value:>6.1}")
+    } else {
+        format!("{value:>6.3}")
+    }
+}
+
+pub struct ChannelEditor<'a> {
+    pub block: &'a ColorBlock,
+    pub mode: SliderMode,
+    pub selected: usize,
+}
+
+impl Widget for ChannelEditor<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // short terminals get whatever rows fit
+        let area = area.intersection(buf.area);
+
+        let title = match self.mode {
+            SliderMode::Hsv => " Adjust Color: HSV ",
+            SliderMode::Rgb => " Adjust Color: RGB ",
+            SliderMode::Oklch => " Adjust Color: OKLCH ",
+        };
+
+        let frame = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        Clear.render(area, buf);
+        frame.render(area, buf);
+
+        let inner = area.inner(margin!(2, 1));
+        if inner.is_empty() {
+            return;
+        }
+
+        let hsv = self.block.hsv;
+        let (r, g, b) = self.block.get_rgb_values();
+
+        Paragraph::new(Line::from(vec![
+            Span::from("      ").bg(Color::Rgb(r, g, b)),
+            Span::from(format!(" {}", self.block.get_hex())).bold(),
+        ]))
+        .render(Rect { height: 1, ..inner }, buf);
+
+        let values = self.mode.values(hsv);
+        // label on the left, value on the right
+        let bar_width = inner.width.saturating_sub(12);
+
+        for (i, spec) in self.mode.channels().iter().enumerate() {
+            let y = inner.y + 2 + i as u16 * 2;
+            if y >= inner.bottom() || bar_width < 2 {
+                break;
+            }
+
+            let label = Line::from(format!(
+                "{} {}",
+                if i == self.selected { ">" } else { " " },
+                spec.label
+            ));
+            let label = if i == self.selected {
+                label.bold()
+            } else {
+                label
+            };
+            buf.set_line(inner.x, y, &label, 4);
+
+            let position = (values[i] - spec.min) / (spec.max - spec.min);
+            let marker = (position * (bar_width - 1) as f32).round() as u16;
+
+            for x in 0..bar_width {
+                let t = x as f32 / (bar_width - 1) as f32;
+                let color = self
+                    .mode
+                    .with_channel(hsv, i, spec.min + t * (spec.max - spec.min));
+                let rgb: Srgb<u8> = Srgb::from_color(color).into_format();
+                let rgb = (rgb.red, rgb.green, rgb.blue);
+
+                let cell = &mut buf[(inner.x + 4 + x, y)];
+                cell.set_bg(Color::Rgb(rgb.0, rgb.1, rgb.2));
+
+                if x == marker {
+                    // dark marker on light colors and the other way round
+                    let fg = if relative_luminance(rgb) > 0.4 {
+                        Color::Black
+                    } else {
+                        Color::White
+                    };
+                    cell.set_symbol("┃").set_fg(fg);
+                } else {
+                    cell.set_symbol(" ");
+                }
+            }
+
+            buf.set_string(
+                inner.x + 5 + bar_width,
+                y,
+                format_value(spec, values[i]),
+                Style::new(),
+            );
+        }
+    }
+}
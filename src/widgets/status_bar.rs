@@ -1,24 +1,96 @@
 use ratatui::{
     layout::Alignment,
     style::{Color, Stylize},
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, Padding, Paragraph, Widget},
 };
 
+use crate::{
+    color_depth::ColorDepth,
+    cvd::CvdMode,
+    generator::ColorSpace,
+    keymap::KeyBinding,
+    notification::{Level, Notification},
+};
+
 #[derive(Default, Debug)]
-pub struct StatusBar {
-    pub message: &'static str,
+pub struct StatusBar<'a> {
+    pub notification: Option<&'a Notification>,
+    pub page: &'a str,
+    pub theory: &'a str,
+    pub block_count: usize,
+    /// Key and label pairs for the bottom line.
+    pub hints: &'a [(KeyBinding, &'static str)],
+    pub seed: Option<u64>,
+    pub color_space: ColorSpace,
+    pub cvd: CvdMode,
+    pub color_depth: ColorDepth,
 }
 
-impl Widget for &StatusBar {
+impl Widget for &StatusBar<'_> {
     fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
         let block = Block::default()
             .bg(Color::Black)
             .padding(Padding::new(0, 0, 1, 1));
 
-        Paragraph::new(Line::from(self.message))
+        let mut left = Line::from(vec![
+            Span::from(format!(" {} ", self.page)).reversed(),
+            Span::from(format!(" {}  {} blocks  ", self.theory, self.block_count)).dim(),
+        ]);
+
+        if let Some(notification) = self.notification {
+            let color = match notification.level {
+                Level::Info => Color::LightGreen,
+                Level::Warn => Color::Yellow,
+                Level::Error => Color::LightRed,
+            };
+
+            left.push_span(Span::from(notification.message.as_str()).fg(color));
+        }
+
+        Paragraph::new(left)
             .alignment(Alignment::Left)
+            .block(block.clone())
+            .render(area, buf);
+
+        let mut info = format!("{:?}", self.color_space).to_lowercase();
+
+        if self.cvd != CvdMode::Off {
+            info = format!("simulating {}  {info}", self.cvd.label());
+        }
+
+        if let Some(seed) = self.seed {
+            info.push_str(&format!("  seed: {seed}"));
+        }
+
+        let mut line = Line::from(format!("{info} ")).dim();
+
+        if self.color_depth != ColorDepth::TrueColor {
+            line.spans.insert(
+                0,
+                Span::from(format!(
+                    "{}: preview approximated  ",
+                    self.color_depth.label()
+                ))
+                .fg(Color::Yellow),
+            );
+        }
+
+        Paragraph::new(line)
+            .alignment(Alignment::Right)
             .block(block)
             .render(area, buf);
+
+        // the bottom padding row doubles as the key hint strip
+        if area.height >= 3 {
+            let mut hints = Line::from(" ");
+
+            for (key, label) in self.hints {
+                hints.push_span(Span::from(key.to_string()).bold());
+                hints.push_span(Span::from(format!(" {label}  ")).dim());
+            }
+
+            buf.set_line(area.x, area.bottom() - 1, &hints, area.width);
+        }
     }
 }
@@ -0,0 +1,109 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::terminal_theme::{ANSI_NAMES, Rgb, TerminalTheme};
+
+fn color(rgb: Rgb) -> Color {
+    Color::Rgb(rgb.0, rgb.1, rgb.2)
+}
+
+/// A fake shell session drawn with the theme's colors.
+pub struct TerminalPreview<'a> {
+    pub theme: &'a TerminalTheme,
+}
+
+impl<'a> TerminalPreview<'a> {
+    pub fn new(theme: &'a TerminalTheme) -> Self {
+        Self { theme }
+    }
+
+    fn ansi(&self, idx: usize) -> Style {
+        Style::new().fg(color(self.theme.ansi[idx]))
+    }
+
+    fn prompt(&self, command: &'static str) -> Line<'static> {
+        Line::from(vec![
+            Span::styled("user@host", self.ansi(2).bold()),
+            Span::styled(" ~/project", self.ansi(4).bold()),
+            Span::styled(" $ ", self.ansi(7)),
+            Span::raw(command),
+        ])
+    }
+}
+
+impl Widget for TerminalPreview<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = self.theme;
+        let fg = color(theme.foreground);
+        let bg = color(theme.background);
+
+        let block = Block::default()
+            .title(" Terminal Theme ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .fg(fg)
+            .bg(bg);
+
+        let mut lines = vec![
+            self.prompt("ls"),
+            Line::from(vec![
+                Span::styled("src  target  ", self.ansi(12).bold()),
+                Span::raw("Cargo.toml  README.md  "),
+                Span::styled("run.sh  ", self.ansi(10).bold()),
+                Span::styled("palette.tar.gz", self.ansi(9)),
+            ]),
+            self.prompt("git diff --stat"),
+            Line::from(vec![
+                Span::raw(" src/app.rs | 12 "),
+                Span::styled("++++++++", self.ansi(2)),
+                Span::styled("----", self.ansi(1)),
+            ]),
+            self.prompt("cargo build"),
+            Line::from(vec![
+                Span::styled("warning", self.ansi(11).bold()),
+                Span::raw(": unused variable: `"),
+                Span::styled("seed", Style::new().bg(color(theme.selection_background))),
+                Span::raw("`"),
+            ]),
+            Line::from(vec![
+                Span::styled("error[E0425]", self.ansi(9).bold()),
+                Span::raw(": cannot find value `palette` in this scope"),
+            ]),
+            Line::from(vec![
+                Span::styled("   --> ", self.ansi(12)),
+                Span::raw("src/main.rs:4:5"),
+            ]),
+            self.prompt("echo $TERM"),
+            Line::from(Span::styled("xterm-256color", self.ansi(6))),
+            Line::from(vec![
+                Span::styled("user@host", self.ansi(2).bold()),
+                Span::styled(" ~/project", self.ansi(4).bold()),
+                Span::styled(" $ ", self.ansi(7)),
+                Span::styled(" ", Style::new().bg(color(theme.cursor))),
+            ]),
+            Line::from(""),
+        ];
+
+        for offset in [0, 8] {
+            let mut spans = Vec::new();
+
+            for (i, name) in ANSI_NAMES.iter().enumerate() {
+                let rgb = theme.ansi[offset + i];
+                spans.push(Span::styled(
+                    format!(" {:<8}", &name[..name.len().min(7)]),
+                    Style::new().bg(color(rgb)).fg(bg),
+                ));
+            }
+
+            lines.push(Line::from(spans));
+        }
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}